@@ -7,26 +7,26 @@ export CARGO_TERM_COLOR=always
 cd crate
 
 echo Check code formatting - And Fix it.
-cargo fmt
+cargo fmt --all
 echo
 
 echo Run Clippy lint checks
-cargo clippy --all-targets --all-features -- -D warnings -D clippy::pedantic -D clippy::cargo
+cargo clippy --workspace --all-targets --all-features -- -D warnings -D clippy::pedantic -D clippy::cargo
 echo
 
 echo Build the project
-cargo build --verbose
-cargo build -r --verbose
+cargo build --workspace --verbose
+cargo build --workspace -r --verbose
 echo
 
 echo Build the docs
-cargo doc -r --no-deps
+cargo doc --workspace -r --no-deps
 echo
 
 echo Run doc tests
-cargo test --doc
+cargo test --workspace --doc
 echo
 
 echo Run tests using cargo-nextest
-cargo nextest run
+cargo nextest run --workspace
 echo
\ No newline at end of file
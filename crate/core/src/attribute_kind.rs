@@ -0,0 +1,3656 @@
+//! What kind of test is this and what are its attributes
+use chrono::{DateTime, Days, Months, NaiveDate};
+use indoc::indoc;
+use std::collections::HashSet;
+use std::env;
+use std::env::consts::{ARCH, OS};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use syn::{parse_quote, Attribute, Error, Path, Result};
+
+use crate::config::{
+    are_known_resources_missing, expanded_available_resources, group_members,
+    has_resources_available, is_test_kind_defined, is_test_kind_included, is_test_resource_defined,
+    is_test_resource_excluded, is_test_resource_listed, names_match, other_kind_age,
+    resource_count_limits, resource_groups, test_kind_excluded_by, today, MissingResourceMode,
+    QuarantineMode, TEST_KIND_ALLOW_FUTURE_UPDATED, TEST_KIND_DEFAULT_PRIORITY, TEST_KIND_DRY_RUN,
+    TEST_KIND_EXCLUDE_ALL_OTHER, TEST_KIND_FORCE_RUN, TEST_KIND_FUTURE_TOLERANCE_DAYS,
+    TEST_KIND_INTEGRATION_AGE, TEST_KIND_MIN_PRIORITY, TEST_KIND_MIN_UPDATED,
+    TEST_KIND_MISSING_RESOURCE_MODE, TEST_KIND_NO_RETRY, TEST_KIND_NO_TIMEOUT, TEST_KIND_PROFILE,
+    TEST_KIND_QUARANTINE, TEST_KIND_RUN_ID, TEST_KIND_RUN_MANUAL, TEST_KIND_SAMPLE_PERCENT,
+    TEST_KIND_SAMPLE_SEED, TEST_KIND_UNIT_AGE, TEST_KIND_UPDATED_POLICY, TEST_KIND_WARN_AGED,
+    TEST_KIND_WARN_STALE_AUTHORING,
+};
+use crate::error::TestKindError;
+use crate::resource::ResourceReq;
+use crate::unit_age::UnitAgeResult;
+
+#[derive(Debug, PartialEq, Eq)]
+/// What kind of Test is this and its attributes.
+///
+/// It is displayed in its canonical form, as it would be given to the `test_kind` macro,
+/// which parses back to the same kind, so tools can read and rewrite the kinds of tests.
+pub enum AttributeKind {
+    /// Unit tests.
+    Unit {
+        /// Last date it was updated.
+        updated: NaiveDate,
+        /// Date it was first created, only recorded for auditing.
+        created: Option<NaiveDate>,
+        /// Maximum age in days of this test, instead of `TEST_KIND_UNIT_AGE`.
+        age: Option<u32>,
+        /// Number of days this test is skipped, instead of `TEST_KIND_UNIT_SKIP`.
+        skip: Option<u32>,
+    },
+    /// Stand alone integration tests.
+    Integration {
+        /// Last date it was updated, if it can age out.
+        updated: Option<NaiveDate>,
+    },
+    /// Benchmarks, which are run by their own harness.
+    Bench,
+    /// Manual tests, which are only run when asked for.
+    Manual,
+    /// Any other tests that have resources.
+    Other {
+        /// Kind of test
+        kind: String,
+        /// Resources it requires.
+        resources: Vec<ResourceReq>,
+        /// Last date it was updated, if it can age out.
+        updated: Option<NaiveDate>,
+    },
+    /// Tests which are more than one kind.
+    Multi {
+        /// Each of the kinds of the test.
+        kinds: Vec<AttributeKind>,
+    },
+}
+
+/// The complete set of attributes given to the `test_kind` macro.
+pub struct TestAttributes {
+    /// What kind of test this is.
+    pub(crate) kind: AttributeKind,
+    /// Test harness attribute to use instead of `#[test]`.
+    pub harness: Option<Path>,
+    /// Test harness attribute to use instead when compiled for `wasm32`.
+    harness_wasm: Option<Path>,
+    /// Constant listing the known resources, registered in the code.
+    pub known_resources: Option<Path>,
+    /// Date after which the test fails to compile.
+    expires: Option<NaiveDate>,
+    /// Operating systems the test runs on, any if empty.
+    os: Vec<String>,
+    /// CPU architectures the test runs on, any if empty.
+    arch: Vec<String>,
+    /// Custom reason given when the test is skipped.
+    reason: Option<String>,
+    /// Priority of the test, higher is more important.
+    priority: i64,
+    /// Is the test quarantined, so its failures are reported rather than failing?
+    quarantine: bool,
+    /// How many times a flaky test is retried before it fails.
+    flaky: Option<u32>,
+    /// How long the test may run before it fails.
+    timeout: Option<Duration>,
+    /// Version of the crate the test was added in.
+    since: Option<String>,
+    /// Cargo features of the crate which must all be enabled for the test to compile.
+    features: Vec<String>,
+    /// People or teams who own the test, to be told when it is skipped.
+    owners: Vec<String>,
+    /// Name of the test function, when it is known.
+    test_name: Option<String>,
+}
+
+/// Format of the dates given to the `test_kind` macro.
+///
+/// Unquoted dates reach the macro as tokens, so `2024-01-01` is `2024 - 01 - 01`.
+/// Spaces in the format match any amount of whitespace, including none, so this
+/// parses dates both with and without the spaces.
+const DATE_FORMAT: &str = "%Y - %m - %d";
+
+/// Options which can be given to every kind of test, as well as the options of its kind.
+const COMMON_OPTIONS: [&str; 13] = [
+    "expires",
+    "os",
+    "arch",
+    "features",
+    "priority",
+    "reason",
+    "owners",
+    "since",
+    "harness",
+    "harness_wasm",
+    "known_resources",
+    "flaky",
+    "timeout",
+];
+
+/// Flags which can be given to the `test_kind` macro, amongst the kind names.
+const FLAGS: &[&str] = &["quarantine"];
+
+/// Known operating systems, as given by `std::env::consts::OS`.
+const KNOWN_OS: &[&str] = &[
+    "linux",
+    "macos",
+    "ios",
+    "tvos",
+    "watchos",
+    "visionos",
+    "freebsd",
+    "dragonfly",
+    "netbsd",
+    "openbsd",
+    "solaris",
+    "illumos",
+    "android",
+    "windows",
+    "fuchsia",
+    "redox",
+    "haiku",
+    "hurd",
+    "aix",
+    "emscripten",
+    "wasi",
+    "horizon",
+    "vxworks",
+    "espidf",
+    "nto",
+    "l4re",
+    "uefi",
+    "cygwin",
+];
+
+/// Known CPU architectures, as given by `std::env::consts::ARCH`.
+const KNOWN_ARCH: &[&str] = &[
+    "x86",
+    "x86_64",
+    "arm",
+    "aarch64",
+    "arm64ec",
+    "loongarch64",
+    "m68k",
+    "csky",
+    "mips",
+    "mips32r6",
+    "mips64",
+    "mips64r6",
+    "powerpc",
+    "powerpc64",
+    "riscv32",
+    "riscv64",
+    "s390x",
+    "sparc",
+    "sparc64",
+    "hexagon",
+    "wasm32",
+    "wasm64",
+    "bpf",
+    "avr",
+    "msp430",
+    "xtensa",
+    "nvptx64",
+    "amdgpu",
+];
+
+/// A single `key=value` option given to the `test_kind` macro.
+#[derive(Clone)]
+struct AttributeOption {
+    /// Name of the option.
+    key: String,
+    /// Its value, with any surrounding quotes removed.
+    value: String,
+}
+
+/// An error for each key which is given more than once, in the order they are first given.
+///
+/// A repeated key is ambiguous, so it is an error rather than one value silently winning.
+fn duplicate_errors(attributes: &String, options: &[AttributeOption]) -> Vec<Error> {
+    let mut keys: Vec<&str> = Vec::new();
+    for option in options {
+        let key = option.key.as_str();
+        if !keys.contains(&key) && options.iter().filter(|other| other.key == key).count() > 1 {
+            keys.push(key);
+        }
+    }
+    keys.into_iter()
+        .map(|key| Error::new_spanned(attributes, format!("`{key}` may only be given once")))
+        .collect()
+}
+
+/// Why a test is skipped, given as a stable token at the start of its reason.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipCategory {
+    /// Its kind is excluded by `TEST_KIND_EXCLUDE`.
+    Excluded,
+    /// Its kind is not in `TEST_KIND_INCLUDE`.
+    NotIncluded,
+    /// A resource it requires is excluded by `TEST_KIND_EXCLUDE_RESOURCE`.
+    ExcludedResources,
+    /// A resource it requires is not available.
+    MissingResources,
+    /// A resource it forbids is available.
+    ForbiddenResources,
+    /// It has aged out.
+    Aged,
+    /// It can not run on this os or arch.
+    Platform,
+    /// Its priority is below `TEST_KIND_MIN_PRIORITY`.
+    Priority,
+    /// It is not in the sample of `TEST_KIND_SAMPLE_PERCENT`.
+    Sampled,
+    /// It has its own `#[ignore]`.
+    Ignored,
+    /// `TEST_KIND_DRY_RUN` is set.
+    DryRun,
+    /// It is a manual test, and `TEST_KIND_RUN_MANUAL` is not set.
+    Manual,
+    /// It is of multiple kinds, which are skipped for different reasons.
+    Multiple,
+    /// It skipped itself with `skip_if!` while it was running.
+    Runtime,
+}
+
+impl SkipCategory {
+    /// The token of the category, such as `missing_resources`.
+    #[must_use]
+    pub fn token(self) -> &'static str {
+        match self {
+            SkipCategory::Excluded => "excluded",
+            SkipCategory::NotIncluded => "not_included",
+            SkipCategory::ExcludedResources => "excluded_resources",
+            SkipCategory::MissingResources => "missing_resources",
+            SkipCategory::ForbiddenResources => "forbidden_resources",
+            SkipCategory::Aged => "aged",
+            SkipCategory::Platform => "platform",
+            SkipCategory::Priority => "priority",
+            SkipCategory::Sampled => "sampled",
+            SkipCategory::Ignored => "ignored",
+            SkipCategory::DryRun => "dry_run",
+            SkipCategory::Manual => "manual",
+            SkipCategory::Multiple => "multiple",
+            SkipCategory::Runtime => "runtime",
+        }
+    }
+}
+
+/// Why a test is skipped.
+///
+/// It is displayed as its category, followed by a description for people,
+/// such as `[test_kind:excluded] Unit tests are excluded by ...`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkipReason {
+    /// The category of the reason.
+    pub category: SkipCategory,
+    /// Description of the reason.
+    pub text: String,
+}
+
+impl SkipReason {
+    /// A reason of the `category`, described by `text`.
+    pub fn new(category: SkipCategory, text: impl Into<String>) -> Self {
+        SkipReason {
+            category,
+            text: text.into(),
+        }
+    }
+
+    /// The token which starts the reason, such as `[test_kind:excluded]`.
+    #[must_use]
+    pub fn prefix(&self) -> String {
+        format!("[test_kind:{}]", self.category.token())
+    }
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.prefix(), self.text)
+    }
+}
+
+/// What to do with a test based on its kind and attributes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestSettings {
+    /// Run the test.
+    Run,
+    /// Silently ignore the test.
+    Ignore,
+    /// Skip the test - with a reason.
+    Skip {
+        /// Reason for skipping.
+        reason: SkipReason,
+    },
+    /// Fail to compile the test - with a reason.
+    Fail {
+        /// Reason for failing.
+        reason: String,
+    },
+}
+
+impl TestSettings {
+    /// Skip the test, for a reason of the `category` described by `text`.
+    pub fn skip(category: SkipCategory, text: impl Into<String>) -> Self {
+        TestSettings::Skip {
+            reason: SkipReason::new(category, text),
+        }
+    }
+
+    /// The reason the test is skipped or fails, if it is.
+    #[must_use]
+    pub fn reason(&self) -> Option<String> {
+        match self {
+            TestSettings::Run | TestSettings::Ignore => None,
+            TestSettings::Skip { reason } => Some(reason.to_string()),
+            TestSettings::Fail { reason } => Some(reason.clone()),
+        }
+    }
+
+    /// The name of what is done with the test: `run`, `ignore`, `skip` or `fail`.
+    #[must_use]
+    pub fn decision(&self) -> &'static str {
+        match self {
+            TestSettings::Run => "run",
+            TestSettings::Ignore => "ignore",
+            TestSettings::Skip { .. } => "skip",
+            TestSettings::Fail { .. } => "fail",
+        }
+    }
+
+    /// What is reported for a test which has its own `#[ignore]`, with its optional `reason`.
+    ///
+    /// A test which would run keeps its own `#[ignore]`, so it is reported as skipped.
+    /// Otherwise what was decided replaces it.
+    #[must_use]
+    pub fn ignored_by_test(&self, reason: Option<&str>) -> TestSettings {
+        match (self, reason) {
+            (TestSettings::Run, Some(reason)) => TestSettings::skip(
+                SkipCategory::Ignored,
+                format!("Ignored by its own `#[ignore]`: {reason}"),
+            ),
+            (TestSettings::Run, None) => {
+                TestSettings::skip(SkipCategory::Ignored, "Ignored by its own `#[ignore]`")
+            }
+            (settings, _) => settings.clone(),
+        }
+    }
+}
+
+/// Split the attribute string on commas, ignoring commas inside quoted strings.
+fn split_parts(attributes: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut part = String::new();
+    let mut quoted = false;
+
+    for c in attributes.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                part.push(c);
+            }
+            ',' if !quoted => parts.push(std::mem::take(&mut part)),
+            _ => part.push(c),
+        }
+    }
+    parts.push(part);
+
+    parts.iter().map(|s| s.trim().to_owned()).collect()
+}
+
+/// Normalize an options value.
+///
+/// Quoted values have their quotes removed, otherwise all whitespace is removed
+/// so that `2023 - 10 - 10` becomes `2023-10-10`.
+/// Whitespace separates resources, so the whitespace in unquoted `resources` is kept,
+/// to be split by `split_resources`.
+fn option_value(key: &str, value: &str) -> String {
+    let value = value.trim();
+    match value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        Some(unquoted) => unquoted.to_owned(),
+        None if split_profile(key).0 == "resources" => value.to_owned(),
+        None => value.chars().filter(|c| !c.is_whitespace()).collect(),
+    }
+}
+
+/// Characters which join the parts of a resource, even when there is whitespace around them,
+/// such as in `postgres >= 14` or `postgres | mysql`.
+const RESOURCE_JOINERS: [char; 4] = ['|', '>', '=', '-'];
+
+/// Split a list of resources on both commas and whitespace, so `db redis`, `db,redis`
+/// and `db, redis` are all `["db", "redis"]`.
+///
+/// Whitespace next to a `RESOURCE_JOINERS` character, or after the `!` of a forbidden resource,
+/// does not separate resources, so `postgres >= 14` is still one resource. An entry between two commas which is empty
+/// is kept, so the extra comma can be reported.
+fn split_resources(resources_str: &str) -> Vec<String> {
+    let mut resources = Vec::new();
+    for entry in resources_str.split(',') {
+        let mut words: Vec<String> = Vec::new();
+        for word in entry.split_whitespace() {
+            match words.last_mut() {
+                Some(last)
+                    if last.ends_with(RESOURCE_JOINERS)
+                        || last.ends_with('!')
+                        || word.starts_with(RESOURCE_JOINERS) =>
+                {
+                    last.push_str(word);
+                }
+                _ => words.push(word.to_owned()),
+            }
+        }
+        if words.is_empty() {
+            resources.push(String::new());
+        }
+        resources.extend(words);
+    }
+    resources
+}
+
+/// Parse the attribute string into its leading names and `key=value` options.
+///
+/// Parts without an `=` which follow an option are appended to that options value,
+/// so that `resources=foo, bar` is a single option with the value `foo,bar`.
+/// So are resources with a minimum version, such as `postgres>=14`.
+/// Except for `FLAGS`, which are always returned with the names.
+/// Repeated `resources` are accumulated, so `resources=foo, resources=bar` is also `foo,bar`.
+fn parse_options(attributes: &str) -> (Vec<String>, Vec<AttributeOption>) {
+    let mut names = Vec::new();
+    let mut options: Vec<AttributeOption> = Vec::new();
+    // The option which parts without an `=` are appended to.
+    let mut last = None;
+
+    for part in split_parts(attributes) {
+        // A resource with a version, such as `postgres>=14`, is not an option.
+        if let Some((key, value)) = part
+            .split_once('=')
+            .filter(|(key, _)| !key.trim_end().ends_with('>'))
+        {
+            // Keys are tokens, so `resources@staging` may have been spaced out.
+            let key: String = key.split_whitespace().collect();
+            let value = option_value(&key, value);
+            let repeated = options
+                .iter()
+                .position(|option| option.key == key && split_profile(&key).0 == "resources");
+            if let Some(index) = repeated {
+                options[index].value.push(',');
+                options[index].value.push_str(&value);
+                last = Some(index);
+            } else {
+                options.push(AttributeOption { key, value });
+                last = Some(options.len() - 1);
+            }
+        } else if let Some(option) = last
+            .map(|index| &mut options[index])
+            .filter(|_| !FLAGS.contains(&part.as_str()))
+        {
+            option.value.push(',');
+            option.value.push_str(&option_value(&option.key, &part));
+        } else {
+            names.push(part);
+        }
+    }
+
+    (names, options)
+}
+
+/// Is this a valid semantic version, such as `1.4.0` or `2.0.0-rc.1+build.5`?
+fn is_semver(version: &str) -> bool {
+    /// Is this a number, without any leading zeros?
+    fn is_number(identifier: &str) -> bool {
+        !identifier.is_empty()
+            && identifier.chars().all(|c| c.is_ascii_digit())
+            && (identifier == "0" || !identifier.starts_with('0'))
+    }
+
+    /// Is this made of only ASCII letters, digits and `-`?
+    fn is_identifier(identifier: &str) -> bool {
+        !identifier.is_empty()
+            && identifier
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    }
+
+    let (version, build) = version.split_once('+').unwrap_or((version, "0"));
+    let (core, pre_release) = version.split_once('-').unwrap_or((version, "0"));
+
+    core.split('.').count() == 3
+        && core.split('.').all(is_number)
+        && pre_release.split('.').all(|identifier| {
+            // Numbers in the pre-release can not have leading zeros, build metadata can.
+            is_identifier(identifier)
+                && (is_number(identifier) || !identifier.chars().all(|c| c.is_ascii_digit()))
+        })
+        && build.split('.').all(is_identifier)
+}
+
+/// Check the name of a kind of test, such as `end2end`, is made of only ASCII letters,
+/// digits, `_` and `-`.
+///
+/// Returns an error message if the name is invalid.
+fn check_kind_name(kind: &str) -> std::result::Result<(), String> {
+    if !kind.is_empty()
+        && kind
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid kind `{kind}`, it may only contain letters, numbers, `_` and `-`"
+        ))
+    }
+}
+
+/// Split an option key into its name and profile, such that `resources@staging`
+/// becomes `("resources", Some("staging"))`.
+fn split_profile(key: &str) -> (&str, Option<&str>) {
+    match key.split_once('@') {
+        Some((key, profile)) => (key, Some(profile)),
+        None => (key, None),
+    }
+}
+
+/// Choose the resources of the `active` profile, from those given for each profile,
+/// or else the `default` resources which were given without a profile.
+///
+/// Returns `None` if no resources were given at all, or an error message if they were
+/// only given for other profiles.
+fn select_profile<T>(
+    default: Option<T>,
+    profiles: Vec<(&str, T)>,
+    active: Option<&str>,
+) -> std::result::Result<Option<T>, String> {
+    let names: Vec<&str> = profiles.iter().map(|(profile, _)| *profile).collect();
+    let selected = active.and_then(|active| {
+        profiles
+            .into_iter()
+            .find(|(profile, _)| names_match(profile, active))
+    });
+    match (selected, default) {
+        (Some((_, resources)), _) => Ok(Some(resources)),
+        (None, Some(default)) => Ok(Some(default)),
+        (None, None) if names.is_empty() => Ok(None),
+        (None, None) => Err(format!(
+            "Resources are only given for the profiles {names:?}, but `TEST_KIND_PROFILE` is {}",
+            active.map_or_else(|| "not set".to_owned(), |active| format!("`{active}`"))
+        )),
+    }
+}
+
+/// Combine every error into one, so they are all reported at once.
+///
+/// Returns `Ok` if there are no errors.
+fn combine_errors(errors: Vec<Error>) -> Result<()> {
+    match errors.into_iter().reduce(|mut combined, err| {
+        combined.combine(err);
+        combined
+    }) {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// Both values, or the errors of either or both of them.
+fn both<A, B>(a: Result<A>, b: Result<B>) -> Result<(A, B)> {
+    match (a, b) {
+        (Ok(a), Ok(b)) => Ok((a, b)),
+        (Err(mut err), Err(other)) => {
+            err.combine(other);
+            Err(err)
+        }
+        (Err(err), Ok(_)) | (Ok(_), Err(err)) => Err(err),
+    }
+}
+
+/// What to do with a test of the `kind` in a dry run, given what would have been done.
+///
+/// Tests which would run or be skipped are skipped, with a reason saying so.
+/// Tests which would be ignored or fail are unchanged.
+fn dry_run(settings: TestSettings, kind: &str) -> TestSettings {
+    match settings {
+        TestSettings::Run => {
+            TestSettings::skip(SkipCategory::DryRun, format!("would run as {kind}"))
+        }
+        TestSettings::Skip { reason } => {
+            TestSettings::skip(SkipCategory::DryRun, format!("would skip anyway: {reason}"))
+        }
+        settings @ (TestSettings::Ignore | TestSettings::Fail { .. }) => settings,
+    }
+}
+
+/// Add the `run_id` of the CI run to the reason a test is skipped, such as `[run=abc123]`,
+/// after its category, so the reason can still be parsed.
+///
+/// Tests which are not skipped, or when there is no `run_id`, are unchanged.
+fn with_run_id(settings: TestSettings, run_id: Option<&str>) -> TestSettings {
+    match (settings, run_id) {
+        (TestSettings::Skip { reason }, Some(run_id)) => {
+            TestSettings::skip(reason.category, format!("[run={run_id}] {}", reason.text))
+        }
+        (settings, _) => settings,
+    }
+}
+
+/// Why a test of the `kind` which requires `count` resources has too few or too many,
+/// given the `min` and `max` it can require.
+fn resource_count_error(
+    kind: &str,
+    count: usize,
+    min: Option<usize>,
+    max: Option<usize>,
+) -> Option<String> {
+    match (min, max) {
+        (Some(min), _) if count < min => Some(format!(
+            "Tests of kind {kind} must require at least {min} resources, not {count}"
+        )),
+        (_, Some(max)) if count > max => Some(format!(
+            "Tests of kind {kind} must require at most {max} resources, not {count}"
+        )),
+        _ => None,
+    }
+}
+
+/// What to do with a test which ages out, other than a unit test, given its `age`.
+///
+/// `tests` describes the test, such as `Integration test`, and `young` decides what to do with
+/// it when it has not aged out. An aging test which runs adds a warning to `warnings`.
+fn aged_decision(
+    age: UnitAgeResult,
+    tests: &str,
+    warnings: &mut Vec<String>,
+    young: impl FnOnce() -> TestSettings,
+) -> TestSettings {
+    match age {
+        UnitAgeResult::Young => young(),
+        UnitAgeResult::Warn(warning) => {
+            let settings = young();
+            if settings == TestSettings::Run {
+                warnings.push(format!("{tests} is aging. {warning}"));
+            }
+            settings
+        }
+        UnitAgeResult::Aged(reason) => TestSettings::skip(SkipCategory::Aged, reason),
+        UnitAgeResult::Old => TestSettings::Ignore,
+    }
+}
+
+/// Is the test `name` in the sample of `percent` of the tests, chosen by the `seed`?
+///
+/// The name and seed are hashed with FNV-1a, and then mixed so similar names are spread
+/// evenly. This is the same on every run and platform, so each test is in or out of the
+/// sample until the seed changes.
+fn is_sampled(name: &str, percent: u32, seed: &str) -> bool {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let hash = seed
+        .bytes()
+        .chain(std::iter::once(0))
+        .chain(name.bytes())
+        .fold(FNV_OFFSET, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+        });
+    let hash = (hash ^ (hash >> 33)).wrapping_mul(0xff51_afd7_ed55_8ccd);
+    let hash = (hash ^ (hash >> 33)).wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    (hash ^ (hash >> 33)) % 100 < u64::from(percent)
+}
+
+/// What to do with a manual test, which is only run when `run_manual` is set.
+fn manual_decision(run_manual: bool) -> TestSettings {
+    if run_manual {
+        TestSettings::Run
+    } else {
+        TestSettings::skip(SkipCategory::Manual, "manual test, run explicitly")
+    }
+}
+
+/// What to do with a test of the `kind` when `exclude_all_other` is set, by
+/// `TEST_KIND_EXCLUDE_ALL_OTHER`.
+///
+/// Every other kind of test is skipped, whatever it is. Built in kinds are unaffected.
+fn all_other_excluded(exclude_all_other: bool, kind: &AttributeKind) -> Option<TestSettings> {
+    (exclude_all_other && matches!(kind, AttributeKind::Other { .. })).then(|| {
+        TestSettings::skip(
+            SkipCategory::Excluded,
+            "all external-resource tests excluded",
+        )
+    })
+}
+
+/// Reason a test is skipped because its kind is excluded by the `pattern` in `TEST_KIND_EXCLUDE`.
+///
+/// `tests` describes the tests of the kind, such as `Unit tests`.
+fn excluded_reason(tests: &str, pattern: &str) -> SkipReason {
+    SkipReason::new(
+        SkipCategory::Excluded,
+        format!("{tests} are excluded by `{pattern}` in TEST_KIND_EXCLUDE"),
+    )
+}
+
+/// Reason a test is skipped because its kind is not in `TEST_KIND_INCLUDE`.
+fn not_included_reason(kind: &str) -> SkipReason {
+    SkipReason::new(
+        SkipCategory::NotIncluded,
+        format!("Kind {kind} not in TEST_KIND_INCLUDE"),
+    )
+}
+
+/// Expand the required resources which are `groups` into the resources in them,
+/// so the resources which are missing are reported rather than the group.
+///
+/// Alternatives, forbidden resources and resources with a version are not expanded.
+/// Resources which are required more than once, through groups, are only required once.
+///
+/// Returns an error message if a resource in a group is invalid.
+fn expand_groups(
+    resources: &[ResourceReq],
+    groups: &[(String, Vec<String>)],
+) -> std::result::Result<Vec<ResourceReq>, String> {
+    let mut expanded: Vec<ResourceReq> = Vec::new();
+    for resource in resources {
+        let members = match resource
+            .plain_name()
+            .and_then(|name| group_members(name, groups))
+        {
+            Some(members) => members
+                .iter()
+                .map(|member| ResourceReq::parse(member))
+                .collect::<std::result::Result<Vec<_>, String>>()?,
+            None => vec![resource.clone()],
+        };
+        for member in members {
+            if !expanded
+                .iter()
+                .any(|resource| resource.to_string() == member.to_string())
+            {
+                expanded.push(member);
+            }
+        }
+    }
+    Ok(expanded)
+}
+
+/// Reason a test is skipped because it requires resources which are `excluded`.
+///
+/// Forbidden resources are not required, so excluding them does not skip the test.
+fn excluded_resources_reason(
+    resources: &[ResourceReq],
+    excluded: impl Fn(&str) -> bool,
+) -> Option<SkipReason> {
+    let excluded: Vec<&str> = resources
+        .iter()
+        .filter(|resource| !resource.is_forbidden())
+        .flat_map(ResourceReq::names)
+        .map(String::as_str)
+        .filter(|name| excluded(name))
+        .collect();
+    let text = match excluded.as_slice() {
+        [] => return None,
+        [resource] => format!("resource {resource} excluded"),
+        resources => format!("resources {} excluded", resources.join(", ")),
+    };
+    Some(SkipReason::new(SkipCategory::ExcludedResources, text))
+}
+
+/// What to do with a test of another kind, given its missing and forbidden resources.
+///
+/// Tests missing a resource are ignored silently when `mode` is `drop`, and fail to compile
+/// when it is `fail`.
+fn resources_decision(
+    kind: &str,
+    missing_resources: &[String],
+    forbidden_resources: &[&String],
+    mode: MissingResourceMode,
+) -> TestSettings {
+    match (missing_resources.is_empty(), forbidden_resources.is_empty()) {
+        (true, true) => TestSettings::Run,
+        (false, _) if mode == MissingResourceMode::Drop => TestSettings::Ignore,
+        (false, _) if mode == MissingResourceMode::Fail => TestSettings::Fail {
+            reason: format!(
+                "Test of kind: {kind} requires {missing_resources:?}, which must be available when resources are required"
+            ),
+        },
+        (false, true) => TestSettings::skip(
+            SkipCategory::MissingResources,
+            format!("Test of kind: {kind} requires {missing_resources:?}"),
+        ),
+        (true, false) => TestSettings::skip(
+            SkipCategory::ForbiddenResources,
+            format!("Test of kind: {kind} forbids available {forbidden_resources:?}"),
+        ),
+        (false, false) => TestSettings::skip(
+            SkipCategory::MissingResources,
+            format!("Test of kind: {kind} requires {missing_resources:?} and forbids available {forbidden_resources:?}"),
+        ),
+    }
+}
+
+/// The order of the options of a kind, in its canonical form.
+const OPTION_ORDER: [&str; 5] = ["resources", "updated", "created", "age", "skip"];
+
+impl AttributeKind {
+    /// The `key=value` options of this attribute kind, in canonical order.
+    ///
+    /// Every kind of a test of multiple kinds is given the same options, so they are only
+    /// listed once.
+    fn options(&self) -> Vec<(&'static str, String)> {
+        let date = |key, date: &NaiveDate| (key, date.format("%Y-%m-%d").to_string());
+        let mut options = Vec::new();
+        match self {
+            AttributeKind::Unit {
+                updated,
+                created,
+                age,
+                skip,
+            } => {
+                options.push(date("updated", updated));
+                options.extend(created.as_ref().map(|created| date("created", created)));
+                options.extend(age.map(|age| ("age", age.to_string())));
+                options.extend(skip.map(|skip| ("skip", skip.to_string())));
+            }
+            AttributeKind::Integration { updated } => {
+                options.extend(updated.as_ref().map(|updated| date("updated", updated)));
+            }
+            AttributeKind::Bench | AttributeKind::Manual => {}
+            AttributeKind::Other {
+                resources, updated, ..
+            } => {
+                let resources: Vec<String> = resources
+                    .iter()
+                    .map(|resource| {
+                        let prefix = if resource.is_forbidden() { "!" } else { "" };
+                        format!("{prefix}{resource}")
+                    })
+                    .collect();
+                options.push(("resources", format!("\"{}\"", resources.join(", "))));
+                options.extend(updated.as_ref().map(|updated| date("updated", updated)));
+            }
+            AttributeKind::Multi { kinds } => {
+                for option in kinds.iter().flat_map(AttributeKind::options) {
+                    if !options.iter().any(|(key, _)| *key == option.0) {
+                        options.push(option);
+                    }
+                }
+            }
+        }
+        options.sort_by_key(|(key, _)| OPTION_ORDER.iter().position(|order| order == key));
+        options
+    }
+
+    /// The name of this attribute kind, multiple kinds are comma separated.
+    pub(crate) fn name(&self) -> String {
+        match *self {
+            AttributeKind::Unit { .. } => "unit".to_owned(),
+            AttributeKind::Integration { .. } => "integration".to_owned(),
+            AttributeKind::Bench => "bench".to_owned(),
+            AttributeKind::Manual => "manual".to_owned(),
+            AttributeKind::Other { ref kind, .. } => kind.clone(),
+            AttributeKind::Multi { ref kinds } => kinds
+                .iter()
+                .map(AttributeKind::name)
+                .collect::<Vec<_>>()
+                .join(","),
+        }
+    }
+
+    /// The names of every resource of this attribute kind.
+    fn resource_names(&self) -> Vec<&String> {
+        match *self {
+            AttributeKind::Unit { .. }
+            | AttributeKind::Integration { .. }
+            | AttributeKind::Bench
+            | AttributeKind::Manual => Vec::new(),
+            AttributeKind::Other { ref resources, .. } => {
+                resources.iter().flat_map(ResourceReq::names).collect()
+            }
+            AttributeKind::Multi { ref kinds } => kinds
+                .iter()
+                .flat_map(AttributeKind::resource_names)
+                .collect(),
+        }
+    }
+
+    /// Is this a benchmark, or one of its kinds is?
+    fn is_bench(&self) -> bool {
+        match *self {
+            AttributeKind::Bench => true,
+            AttributeKind::Multi { ref kinds } => kinds.iter().any(AttributeKind::is_bench),
+            _ => false,
+        }
+    }
+
+    /// Is this attribute kind included?
+    fn is_included(&self) -> bool {
+        match *self {
+            AttributeKind::Unit { .. } => is_test_kind_included("unit"),
+            AttributeKind::Integration { .. } => is_test_kind_included("integration"),
+            AttributeKind::Bench => is_test_kind_included("bench"),
+            AttributeKind::Manual => is_test_kind_included("manual"),
+            AttributeKind::Other { ref kind, .. } => is_test_kind_included(kind.as_str()),
+            AttributeKind::Multi { ref kinds } => kinds.iter().any(AttributeKind::is_included),
+        }
+    }
+
+    /// Parse a date relative to today, such as `90d` for 90 days ago.
+    ///
+    /// The count can be in days (`d`), weeks (`w`) or months (`mo`).
+    ///
+    /// Returns `None` if the date is not relative, or an error if it can not be parsed.
+    fn parse_relative_date(
+        date_str: &str,
+    ) -> std::result::Result<Option<NaiveDate>, TestKindError> {
+        let Some(split) = date_str.find(|c: char| !c.is_ascii_digit()) else {
+            return Ok(None);
+        };
+        let (count, unit) = date_str.split_at(split);
+        if count.is_empty() || !unit.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Ok(None);
+        }
+
+        let invalid = || {
+            TestKindError::InvalidDate(format!("Invalid relative date `{date_str}`. Must be a number of days (`90d`), weeks (`2w`) or months (`6mo`)."))
+        };
+
+        let count: u32 = count.parse().map_err(|_| invalid())?;
+        let today = today();
+        let date = match unit {
+            "d" => today.checked_sub_days(Days::new(count.into())),
+            "w" => today.checked_sub_days(Days::new(u64::from(count).saturating_mul(7))),
+            "mo" => today.checked_sub_months(Months::new(count)),
+            _ => return Err(invalid()),
+        };
+
+        date.map(Some).ok_or_else(invalid)
+    }
+
+    /// Parse a date for the unit test kind, given by its `key`.
+    ///
+    /// Date has the format `YYYY-MM-DD`, or is an RFC3339 timestamp
+    /// such as `2024-03-14T09:30:00Z`, in which case only its date is used.
+    /// It can also be relative to today, such as `90d`, see `parse_relative_date`.
+    /// Or read from an env var when the test is compiled, such as `env:MY_DATE_VAR`.
+    /// Or, for `updated="git"`, read from the env var for the `test_name`, see `git_date_var`.
+    ///
+    /// Returns an error if the date is invalid.
+    /// Date must be:
+    /// * no earlier than `TEST_KIND_MIN_UPDATED`, by default October 10, 2023;
+    /// * allowed by `TEST_KIND_UPDATED_POLICY`, which allows any date by default;
+    /// * and no more than `TEST_KIND_FUTURE_TOLERANCE_DAYS` into the future, by default 2 days,
+    ///   unless `TEST_KIND_ALLOW_FUTURE_UPDATED` is set, when it is clamped to today instead.
+    fn parse_updated(
+        key: &str,
+        date_str: &str,
+        test_name: Option<&str>,
+    ) -> std::result::Result<NaiveDate, TestKindError> {
+        let env_date;
+        let date_str = if date_str == "git" {
+            let env_var = AttributeKind::git_date_var(key, test_name)?;
+            env_date = env::var(&env_var).map_err(|_| {
+                TestKindError::InvalidDate(format!(
+                    "`{key}=git` is read from the env var `{env_var}`, which is not set. \
+                     Set it from a build script with `cargo:rustc-env={env_var}=YYYY-MM-DD`"
+                ))
+            })?;
+            env_date.trim()
+        } else if let Some(env_var) = date_str.strip_prefix("env:") {
+            env_date = env::var(env_var).map_err(|_| {
+                TestKindError::InvalidDate(format!(
+                    "`{key}` is read from the env var `{env_var}`, which is not set"
+                ))
+            })?;
+            env_date.trim()
+        } else {
+            date_str
+        };
+
+        let relative_date = AttributeKind::parse_relative_date(date_str)?;
+        let date = match relative_date
+            .map_or_else(|| NaiveDate::parse_from_str(date_str, DATE_FORMAT), Ok)
+            .or_else(|err| {
+                DateTime::parse_from_rfc3339(date_str)
+                    .map(|timestamp| timestamp.date_naive())
+                    .map_err(|_| err)
+            }) {
+            Ok(date) => date,
+            Err(err) => {
+                return Err(TestKindError::InvalidDate(format!(
+                    "Invalid date format: {err:?}"
+                )))
+            }
+        };
+
+        // Validate the date
+        let min_date = match &*TEST_KIND_MIN_UPDATED {
+            Ok(min_date) => *min_date,
+            Err(err) => return Err(TestKindError::InvalidDate(err.clone())),
+        };
+        let tolerance = *TEST_KIND_FUTURE_TOLERANCE_DAYS;
+        let today = today();
+        let max_date = AttributeKind::max_updated_date(today, tolerance);
+
+        if date < min_date {
+            return Err(TestKindError::InvalidDate(format!(
+                "`{key}={date}` must not be before {}.",
+                min_date.format("%-d %B %Y")
+            )));
+        }
+        TEST_KIND_UPDATED_POLICY
+            .check(key, date)
+            .map_err(TestKindError::InvalidDate)?;
+
+        AttributeKind::future_updated_date(date, today, max_date, *TEST_KIND_ALLOW_FUTURE_UPDATED)
+            .ok_or_else(|| {
+                TestKindError::InvalidDate(format!("`{key}={date}` must not be more than {tolerance} days after the current date. Max date = {max_date}."))
+            })
+    }
+
+    /// The env var which `updated="git"` is read from for the test function `test_name`.
+    ///
+    /// It is `TEST_KIND_GIT_DATE_` followed by the name in upper case, so `my_test` is read from
+    /// `TEST_KIND_GIT_DATE_MY_TEST`.
+    ///
+    /// Returns an error for any other `key`, or if there is no test function.
+    fn git_date_var(
+        key: &str,
+        test_name: Option<&str>,
+    ) -> std::result::Result<String, TestKindError> {
+        if key != "updated" {
+            return Err(TestKindError::InvalidDate(format!(
+                "`{key}=git` is not supported, only `updated` can be read from git"
+            )));
+        }
+        let test_name = test_name.ok_or_else(|| {
+            TestKindError::InvalidDate(
+                "`updated=git` can only be used on a test function, to name its env var".to_owned(),
+            )
+        })?;
+        Ok(format!(
+            "TEST_KIND_GIT_DATE_{}",
+            test_name.trim_start_matches("r#").to_uppercase()
+        ))
+    }
+
+    /// The updated `date`, if it is no later than `max_date`.
+    ///
+    /// When `allow_future`, any date after `today` is clamped to it instead, so it ages as if
+    /// it was updated today.
+    ///
+    /// Returns `None` if the date is too far in the future.
+    fn future_updated_date(
+        date: NaiveDate,
+        today: NaiveDate,
+        max_date: NaiveDate,
+        allow_future: bool,
+    ) -> Option<NaiveDate> {
+        if allow_future {
+            Some(date.min(today))
+        } else if date > max_date {
+            None
+        } else {
+            Some(date)
+        }
+    }
+
+    /// The latest date a test can be updated, `tolerance` days after `today`.
+    ///
+    /// A tolerance past the last representable date allows any date.
+    fn max_updated_date(today: NaiveDate, tolerance: u32) -> NaiveDate {
+        today
+            .checked_add_days(Days::new(tolerance.into()))
+            .unwrap_or(NaiveDate::MAX)
+    }
+
+    /// Parse a number of days given as the option `key`.
+    ///
+    /// Returns an error if it is not a whole number of days.
+    fn parse_days(attributes: &String, key: &str, days: &str) -> Result<u32> {
+        days.trim().parse().map_err(|_| {
+            Error::new_spanned(
+                attributes,
+                format!("`{key}={days}` is invalid, it must be a whole number of days"),
+            )
+        })
+    }
+
+    /// Parse the dates, and any age settings, of the unit test kind.
+    ///
+    /// Returns an error if either date or number of days is invalid, or it was created
+    /// after it was updated.
+    fn parse_unit(
+        attributes: &String,
+        test_name: Option<&str>,
+        updated: &str,
+        created: Option<&str>,
+        age: Option<&str>,
+        skip: Option<&str>,
+    ) -> Result<Self> {
+        let days = |key, days: Option<&str>| {
+            days.map(|days| AttributeKind::parse_days(attributes, key, days))
+                .transpose()
+        };
+        let ((updated, created), (age, skip)) = both(
+            both(
+                AttributeKind::parse_updated("updated", updated, test_name)
+                    .map_err(|err| err.spanned(attributes)),
+                created
+                    .map(|created| AttributeKind::parse_updated("created", created, test_name))
+                    .transpose()
+                    .map_err(|err| err.spanned(attributes)),
+            ),
+            both(days("age", age), days("skip", skip)),
+        )?;
+
+        if let Some(created) = created {
+            if created > updated {
+                return Err(TestKindError::InvalidDate(format!(
+                    "`created={created}` must not be after `updated={updated}`."
+                ))
+                .spanned(attributes));
+            }
+        }
+
+        Ok(Self::Unit {
+            updated,
+            created,
+            age,
+            skip,
+        })
+    }
+
+    /// Parse the list of resources for the given kind
+    ///
+    /// Each resource may be a group of alternatives separated by `|`, of which only one
+    /// is required, or prefixed with `!` when it must not be available.
+    ///
+    /// When the test has `registered` known resources, resources which are not in
+    /// `TEST_KIND_KNOWN_RESOURCES` are checked against them when the test is compiled instead.
+    ///
+    /// Returns an error if the list of resources is invalid, or not unique
+    ///
+    fn parse_resources(
+        kind: &str,
+        attributes: &String,
+        resources_str: &str,
+        registered: bool,
+    ) -> Result<Vec<ResourceReq>> {
+        let (resources, errors) = AttributeKind::check_resources(kind, resources_str, registered);
+        combine_errors(errors.iter().map(|err| err.spanned(attributes)).collect())?;
+        Ok(resources)
+    }
+
+    /// The kind of a test from its kind, `updated` date and `resources`, as they are given to
+    /// `decision_for`, without any of the options which every kind takes.
+    ///
+    /// Returns everything which is wrong with them if they are invalid.
+    pub(crate) fn from_decision(
+        kind: &str,
+        updated: Option<&str>,
+        resources: &[&str],
+    ) -> std::result::Result<Self, Vec<TestKindError>> {
+        if let Err(message) = check_kind_name(kind) {
+            return Err(vec![TestKindError::InvalidAttributes(message)]);
+        }
+
+        let mut errors = Vec::new();
+        let given = [
+            ("updated", updated.is_some()),
+            ("resources", !resources.is_empty()),
+        ];
+        let unknown: Vec<&str> = given
+            .iter()
+            .filter(|(key, given)| *given && !AttributeKind::takes_option(kind, key))
+            .map(|(key, _)| *key)
+            .collect();
+        if !unknown.is_empty() {
+            errors.push(TestKindError::InvalidAttributes(format!(
+                "Invalid options for test kind '{kind}': {unknown:?}, it takes {:?}",
+                AttributeKind::option_keys(kind)
+            )));
+        }
+
+        let parsed = match kind {
+            "unit" | "integration" | "bench" | "manual" => None,
+            _ if resources.is_empty() => {
+                errors.push(if is_test_kind_defined(kind) {
+                    TestKindError::InvalidAttributes(format!(
+                        "Test kind '{kind}' requires `resources=<comma separated list of \
+                         resources>`"
+                    ))
+                } else {
+                    TestKindError::UndefinedKind {
+                        kind: kind.to_owned(),
+                    }
+                });
+                None
+            }
+            _ => {
+                let entries = resources.iter().map(|resource| {
+                    ResourceReq::parse(resource).map_err(TestKindError::InvalidResources)
+                });
+                let (parsed, resource_errors) =
+                    AttributeKind::check_resource_entries(kind, entries.collect(), false);
+                errors.extend(resource_errors);
+                Some(parsed)
+            }
+        };
+
+        if kind == "unit" && updated.is_none() {
+            errors.push(TestKindError::InvalidAttributes(
+                "Test kind 'unit' requires `updated=YYYY-MM-DD`".to_owned(),
+            ));
+        }
+        let updated = match updated
+            .map(|updated| AttributeKind::parse_updated("updated", updated, None))
+            .transpose()
+        {
+            Ok(updated) => updated,
+            Err(err) => {
+                errors.push(err);
+                None
+            }
+        };
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(match (kind, updated) {
+            ("unit", Some(updated)) => Self::Unit {
+                updated,
+                created: None,
+                age: None,
+                skip: None,
+            },
+            ("integration", updated) => Self::Integration { updated },
+            ("bench", _) => Self::Bench,
+            ("manual", _) => Self::Manual,
+            (kind, updated) => Self::Other {
+                kind: kind.to_owned(),
+                resources: parsed.unwrap_or_default(),
+                updated,
+            },
+        })
+    }
+
+    /// The resources in the list of resources for the given kind, and everything which
+    /// is wrong with them, see `parse_resources`.
+    fn check_resources(
+        kind: &str,
+        resources_str: &str,
+        registered: bool,
+    ) -> (Vec<ResourceReq>, Vec<TestKindError>) {
+        // Without any resources, there are no empty entries either.
+        let entries = if resources_str.trim().is_empty() {
+            Vec::new()
+        } else {
+            split_resources(resources_str)
+        };
+        let entries = entries.iter().map(|resource| {
+            // Empty entries are typos, such as a trailing comma, not resources.
+            if resource.trim().is_empty() {
+                Err(TestKindError::InvalidResources(format!(
+                    "Empty resource in `resources={resources_str}`, remove the extra `,`"
+                )))
+            } else {
+                ResourceReq::parse(resource).map_err(TestKindError::InvalidResources)
+            }
+        });
+        AttributeKind::check_resource_entries(kind, entries.collect(), registered)
+    }
+
+    /// The resources for the given kind, from each of its parsed `entries`, and everything
+    /// which is wrong with them, see `check_resources`.
+    fn check_resource_entries(
+        kind: &str,
+        entries: Vec<std::result::Result<ResourceReq, TestKindError>>,
+        registered: bool,
+    ) -> (Vec<ResourceReq>, Vec<TestKindError>) {
+        let mut errors = Vec::new();
+        if !is_test_kind_defined(kind) {
+            errors.push(TestKindError::UndefinedKind {
+                kind: kind.to_owned(),
+            });
+        }
+
+        if are_known_resources_missing() && !registered {
+            errors.push(TestKindError::InvalidResources(
+                "`TEST_KIND_DEFINED` is set, so `TEST_KIND_KNOWN_RESOURCES` must also be set"
+                    .to_owned(),
+            ));
+        }
+
+        if entries.is_empty() {
+            errors.push(TestKindError::InvalidResources(
+                "At least one resource must be specified".to_owned(),
+            ));
+        }
+
+        let mut resources = Vec::new();
+        for entry in entries {
+            match entry {
+                Ok(resource) => resources.push(resource),
+                Err(err) => errors.push(err),
+            }
+        }
+        let names: Vec<String> = resources
+            .iter()
+            .flat_map(ResourceReq::names)
+            .cloned()
+            .collect();
+
+        if names.iter().any(|name| name.starts_with('!')) {
+            errors.push(TestKindError::InvalidResources(
+                "Forbidden resources can not be alternatives".to_owned(),
+            ));
+        }
+
+        let resources = match expand_groups(&resources, resource_groups()) {
+            Ok(expanded) => expanded,
+            Err(message) => {
+                errors.push(TestKindError::InvalidResources(message));
+                resources
+            }
+        };
+
+        // Groups are expanded, so only the resources in them need to be known.
+        let unknown_resources: Vec<String> = resources
+            .iter()
+            .flat_map(ResourceReq::names)
+            .filter(|r| !is_test_resource_defined(r))
+            .cloned()
+            .collect();
+        if !unknown_resources.is_empty() && !registered {
+            errors.push(TestKindError::UnknownResources {
+                resources: unknown_resources,
+            });
+        }
+
+        // Forbidden resources are not required, so they are not counted.
+        // A group counts as every resource in it.
+        let count = resources
+            .iter()
+            .filter(|resource| !resource.is_forbidden())
+            .count();
+        let (min, max) = resource_count_limits(kind);
+        if let Some(message) = resource_count_error(kind, count, min, max) {
+            errors.push(TestKindError::InvalidResources(message));
+        }
+
+        let unique_set: HashSet<_> = names.iter().cloned().collect();
+        if names.len() != unique_set.len() {
+            errors.push(TestKindError::DuplicateResource);
+        }
+
+        (resources, errors)
+    }
+
+    /// Parse the resources of the kind, given without a profile as `resources` or for a
+    /// profile as `resources@<profile>`, and choose those for `TEST_KIND_PROFILE`.
+    ///
+    /// Every list is parsed, so they are checked whichever profile is active.
+    ///
+    /// Returns `None` if no resources were given, or an error if any list is invalid, or
+    /// resources were only given for other profiles.
+    fn parse_profile_resources(
+        kind: &str,
+        attributes: &String,
+        options: &[AttributeOption],
+        registered: bool,
+    ) -> Result<Option<Vec<ResourceReq>>> {
+        let mut default = None;
+        let mut profiles = Vec::new();
+        let mut errors = Vec::new();
+        for option in options {
+            if let ("resources", profile) = split_profile(&option.key) {
+                match AttributeKind::parse_resources(kind, attributes, &option.value, registered) {
+                    Ok(resources) => match profile {
+                        Some(profile) => profiles.push((profile, resources)),
+                        None => default = Some(resources),
+                    },
+                    Err(err) => errors.push(err),
+                }
+            }
+        }
+        combine_errors(errors)?;
+
+        select_profile(default, profiles, TEST_KIND_PROFILE.as_deref())
+            .map_err(|message| Error::new_spanned(attributes, message))
+    }
+
+    /// Parse a test which has multiple kinds.
+    ///
+    /// Each kind takes the options which apply to it, `updated`, `created`, `age` and `skip`
+    /// for `unit` tests and `resources` for every other kind except `integration`.
+    ///
+    /// Returns an error if a kind is repeated, or an option is not used by any kind.
+    fn parse_multi(
+        attributes: &String,
+        test_name: Option<&str>,
+        names: &[String],
+        options: &[AttributeOption],
+        registered: bool,
+    ) -> Result<Self> {
+        let unique_set: HashSet<_> = names.iter().collect();
+        if names.len() != unique_set.len() {
+            return Err(TestKindError::DuplicateKind.spanned(attributes));
+        }
+
+        let mut used_options = HashSet::new();
+        let mut kinds = Vec::new();
+        let mut errors = Vec::new();
+        for name in names {
+            let kind_options: Vec<AttributeOption> = options
+                .iter()
+                .filter(|option| AttributeKind::takes_option(name, &option.key))
+                .cloned()
+                .collect();
+            used_options.extend(kind_options.iter().map(|option| option.key.clone()));
+            match AttributeKind::from_parts(
+                attributes,
+                test_name,
+                std::slice::from_ref(name),
+                &kind_options,
+                registered,
+            ) {
+                Ok(kind) => kinds.push(kind),
+                Err(err) => errors.push(err),
+            }
+        }
+
+        let unused_options: Vec<&str> = options
+            .iter()
+            .map(|option| option.key.as_str())
+            .filter(|key| !used_options.contains(*key))
+            .collect();
+        if !unused_options.is_empty() {
+            let mut keys: Vec<&str> = Vec::new();
+            for key in names
+                .iter()
+                .flat_map(|name| AttributeKind::option_keys(name))
+            {
+                if !keys.contains(key) {
+                    keys.push(key);
+                }
+            }
+            errors.push(Error::new_spanned(
+                attributes,
+                format!(
+                    "Options not used by any of the test kinds: {unused_options:?}, they take \
+                     {keys:?}, and every kind takes {COMMON_OPTIONS:?}"
+                ),
+            ));
+        }
+
+        combine_errors(errors)?;
+        Ok(Self::Multi { kinds })
+    }
+
+    /// The options which are specific to a kind of test.
+    fn option_keys(kind: &str) -> &'static [&'static str] {
+        match kind {
+            "unit" => &["updated", "created", "age", "skip"],
+            "integration" => &["updated"],
+            "bench" | "manual" => &[],
+            _ => &["resources", "updated"],
+        }
+    }
+
+    /// Does the kind take the option `key`?
+    ///
+    /// Resources may also be given for a profile, as `resources@<profile>`.
+    fn takes_option(kind: &str, key: &str) -> bool {
+        let (key, profile) = split_profile(key);
+        AttributeKind::option_keys(kind).contains(&key)
+            && profile.is_none_or(|profile| key == "resources" && !profile.is_empty())
+    }
+
+    /// Convert the kind names and kind specific options of the macro into a `AttributeKind`.
+    ///
+    /// * `attributes`: The full attribute string, used for error reporting.
+    /// * `test_name`: The name of the test function, if known, see `git_date_var`.
+    /// * `names`: The leading names given to the macro.
+    /// * `options`: The `key=value` options which are specific to the kind.
+    /// * `registered`: Are there known resources registered in the code, see `parse_resources`.
+    ///
+    /// Returns an error if the parameters are invalid.
+    fn from_parts(
+        attributes: &String,
+        test_name: Option<&str>,
+        names: &[String],
+        options: &[AttributeOption],
+        registered: bool,
+    ) -> Result<Self> {
+        if names.len() > 1 {
+            return AttributeKind::parse_multi(attributes, test_name, names, options, registered);
+        }
+
+        let [kind] = names else {
+            let msg = indoc! {"
+                Invalid attribute format.
+                Must be one of:
+                 * unit, updated=YYYY-MM-DD, [created=YYYY-MM-DD], [age=<days>], [skip=<days>]
+                 * integration
+                 * bench
+                 * manual
+                 * <something>, resources=<comma separated list of resources>, [updated=YYYY-MM-DD]
+                 * <kind>, <kind>, <options of each kind>
+                Options may be given in any order.
+            "};
+            return Err(Error::new_spanned(attributes, msg));
+        };
+        let kind = kind.as_str();
+        let keys = AttributeKind::option_keys(kind);
+
+        if kind == "integration" && options.iter().any(|option| option.key == "resources") {
+            return Err(Error::new_spanned(
+                attributes,
+                "integration tests cannot declare resources; use a custom kind instead",
+            ));
+        }
+        if kind == "unit"
+            && options
+                .iter()
+                .any(|option| split_profile(&option.key).0 == "resources")
+        {
+            return Err(Error::new_spanned(
+                attributes,
+                "unit tests cannot require resources",
+            ));
+        }
+
+        let mut errors = Vec::new();
+        let unknown: Vec<&str> = options
+            .iter()
+            .map(|option| option.key.as_str())
+            .filter(|key| !AttributeKind::takes_option(kind, key))
+            .collect();
+        if !unknown.is_empty() {
+            errors.push(Error::new_spanned(
+                attributes,
+                format!(
+                    "Invalid options for test kind '{kind}': {unknown:?}, it takes {keys:?}, \
+                     and every kind takes {COMMON_OPTIONS:?}"
+                ),
+            ));
+        }
+        let option = |key: &str| {
+            options
+                .iter()
+                .find(|option| option.key == key)
+                .map(|option| option.value.as_str())
+        };
+        let missing = |required: &str| {
+            Error::new_spanned(
+                attributes,
+                format!("Test kind '{kind}' requires `{required}`"),
+            )
+        };
+
+        let parsed = match kind {
+            "unit" => match option("updated") {
+                Some(updated) => AttributeKind::parse_unit(
+                    attributes,
+                    test_name,
+                    updated,
+                    option("created"),
+                    option("age"),
+                    option("skip"),
+                ),
+                None => Err(missing("updated=YYYY-MM-DD")),
+            },
+            "integration" => option("updated")
+                .map(|updated| AttributeKind::parse_updated("updated", updated, test_name))
+                .transpose()
+                .map(|updated| Self::Integration { updated })
+                .map_err(|err| err.spanned(attributes)),
+            "bench" => Ok(Self::Bench),
+            "manual" => Ok(Self::Manual),
+            _ => both(
+                AttributeKind::parse_profile_resources(kind, attributes, options, registered),
+                option("updated")
+                    .map(|updated| AttributeKind::parse_updated("updated", updated, test_name))
+                    .transpose()
+                    .map_err(|err| err.spanned(attributes)),
+            )
+            .and_then(|(resources, updated)| match resources {
+                Some(resources) => Ok(Self::Other {
+                    kind: kind.to_owned(),
+                    resources,
+                    updated,
+                }),
+                None => Err(missing("resources=<comma separated list of resources>")),
+            }),
+        };
+
+        // Every invalid option is reported, along with any errors in their values.
+        let ((), parsed) = both(combine_errors(errors), parsed)?;
+        Ok(parsed)
+    }
+
+    /// What to do with an other kind of test, which has not aged out?
+    fn what_to_do_other(kind: &str, resources: &[ResourceReq]) -> TestSettings {
+        if let Some(pattern) = test_kind_excluded_by(kind) {
+            TestSettings::Skip {
+                reason: excluded_reason(&format!("Test of kind: {kind}"), pattern),
+            }
+        } else if !is_test_kind_included(kind) {
+            TestSettings::Skip {
+                reason: not_included_reason(kind),
+            }
+        } else if let Some(reason) = excluded_resources_reason(resources, is_test_resource_excluded)
+        {
+            TestSettings::Skip { reason }
+        } else {
+            let (forbidden_resources, missing_resources): (Vec<_>, Vec<_>) =
+                has_resources_available(resources)
+                    .into_iter()
+                    .partition(ResourceReq::is_forbidden);
+            let missing_resources: Vec<String> = missing_resources
+                .iter()
+                .map(|resource| resource.describe_missing(expanded_available_resources()))
+                .collect();
+            let forbidden_resources: Vec<&String> = forbidden_resources
+                .iter()
+                .flat_map(ResourceReq::names)
+                .collect();
+
+            resources_decision(
+                kind,
+                &missing_resources,
+                &forbidden_resources,
+                *TEST_KIND_MISSING_RESOURCE_MODE,
+            )
+        }
+    }
+
+    /// What to do with a test of multiple kinds?
+    ///
+    /// Tests of multiple kinds run if any of their kinds would run.
+    fn what_to_do_multi(kinds: Vec<AttributeKind>, warnings: &mut Vec<String>) -> TestSettings {
+        let settings: Vec<TestSettings> = kinds
+            .into_iter()
+            .map(|kind| kind.what_to_do(warnings))
+            .collect();
+        // A kind which fails always fails the test, even if another kind would run.
+        if let Some(fail) = settings
+            .iter()
+            .find(|setting| matches!(setting, TestSettings::Fail { .. }))
+        {
+            fail.clone()
+        } else if settings
+            .iter()
+            .any(|setting| matches!(setting, TestSettings::Run))
+        {
+            TestSettings::Run
+        } else {
+            // Otherwise they are only ignored if every kind is ignored.
+            let mut reasons: Vec<SkipReason> = settings
+                .into_iter()
+                .filter_map(|setting| match setting {
+                    TestSettings::Skip { reason } => Some(reason),
+                    TestSettings::Run | TestSettings::Ignore | TestSettings::Fail { .. } => None,
+                })
+                .collect();
+            match reasons.len() {
+                0 => TestSettings::Ignore,
+                1 => TestSettings::Skip {
+                    reason: reasons.remove(0),
+                },
+                // Each kind keeps its own category in the description.
+                _ => {
+                    let reasons: Vec<String> = reasons.iter().map(ToString::to_string).collect();
+                    TestSettings::skip(SkipCategory::Multiple, reasons.join("; "))
+                }
+            }
+        }
+    }
+
+    /// What to do with a unit test, which has not aged out?
+    fn what_to_do_young_unit(&self) -> TestSettings {
+        if let Some(pattern) = test_kind_excluded_by("unit") {
+            TestSettings::Skip {
+                reason: excluded_reason("Unit tests", pattern),
+            }
+        } else if !self.is_included() {
+            TestSettings::Skip {
+                reason: not_included_reason("unit"),
+            }
+        } else {
+            TestSettings::Run
+        }
+    }
+
+    /// What to do with an integration test, which has not aged out, a benchmark or a manual test?
+    ///
+    /// They are only excluded when requested. Manual tests are only run when asked for.
+    fn what_to_do_built_in(&self) -> TestSettings {
+        if let Some(pattern) = test_kind_excluded_by(&self.name()) {
+            let tests = match self {
+                AttributeKind::Bench => "Benchmarks",
+                AttributeKind::Manual => "Manual tests",
+                _ => "Integration tests",
+            };
+            TestSettings::Skip {
+                reason: excluded_reason(tests, pattern),
+            }
+        } else if !self.is_included() {
+            TestSettings::Skip {
+                reason: not_included_reason(&self.name()),
+            }
+        } else if matches!(self, AttributeKind::Manual) {
+            manual_decision(*TEST_KIND_RUN_MANUAL)
+        } else {
+            TestSettings::Run
+        }
+    }
+
+    /// What to do with this particular test case?
+    ///
+    /// Any warnings about the test are added to `warnings`.
+    pub(crate) fn what_to_do(self, warnings: &mut Vec<String>) -> TestSettings {
+        if let Some(settings) = all_other_excluded(*TEST_KIND_EXCLUDE_ALL_OTHER, &self) {
+            return settings;
+        }
+
+        match self {
+            AttributeKind::Unit {
+                updated, age, skip, ..
+            } => {
+                let age = TEST_KIND_UNIT_AGE
+                    .with_overrides(age, skip)
+                    .unit_aged_out(updated);
+                if *TEST_KIND_WARN_STALE_AUTHORING {
+                    warnings.extend(age.stale_warning(updated));
+                }
+                match age {
+                    // We only run Young unit tests.
+                    UnitAgeResult::Young => self.what_to_do_young_unit(),
+                    // Aging tests still run, but warn that they will soon be skipped.
+                    UnitAgeResult::Warn(warning) => {
+                        let settings = self.what_to_do_young_unit();
+                        if settings == TestSettings::Run {
+                            warnings.push(format!("Unit test is aging. {warning}"));
+                        }
+                        settings
+                    }
+                    // Recently Aged tests are skipped with a message.
+                    UnitAgeResult::Aged(reason) => {
+                        if *TEST_KIND_WARN_AGED {
+                            warnings.push(format!("Unit test has aged out. {reason}"));
+                        }
+                        TestSettings::skip(SkipCategory::Aged, reason)
+                    }
+                    // Older than that we just inhibit them.
+                    UnitAgeResult::Old => TestSettings::Ignore,
+                }
+            }
+
+            // Integration tests only age out with an updated date, and
+            // `TEST_KIND_INTEGRATION_AGE`.
+            AttributeKind::Integration { updated } => {
+                let age = updated
+                    .zip(TEST_KIND_INTEGRATION_AGE.as_ref())
+                    .map_or(UnitAgeResult::Young, |(updated, age)| {
+                        age.unit_aged_out(updated)
+                    });
+                aged_decision(age, "Integration test", warnings, || {
+                    self.what_to_do_built_in()
+                })
+            }
+
+            AttributeKind::Bench | AttributeKind::Manual => self.what_to_do_built_in(),
+
+            AttributeKind::Other {
+                kind,
+                resources,
+                updated,
+            } => {
+                // Other kinds only age out with an updated date, and an age for their kind.
+                let age = updated
+                    .zip(other_kind_age(&kind))
+                    .map_or(UnitAgeResult::Young, |(updated, age)| {
+                        age.unit_aged_out(updated)
+                    });
+                aged_decision(age, &format!("Test of kind: {kind}"), warnings, || {
+                    AttributeKind::what_to_do_other(&kind, &resources)
+                })
+            }
+
+            AttributeKind::Multi { kinds } => AttributeKind::what_to_do_multi(kinds, warnings),
+        }
+    }
+}
+
+impl TestAttributes {
+    /// Parse the test harness attribute path.
+    ///
+    /// Harness has the format `harness="path::to::attribute"`
+    ///
+    /// Returns an error if the harness is not a valid path.
+    fn parse_harness(attributes: &String, harness_str: &str) -> Result<Path> {
+        syn::parse_str(harness_str).map_err(|err| {
+            Error::new_spanned(
+                attributes,
+                format!("Invalid test harness `{harness_str}`: {err}"),
+            )
+        })
+    }
+
+    /// Parse the path of the constant listing the known resources.
+    ///
+    /// Known resources have the format `known_resources=path::to::KNOWN_RESOURCES`
+    ///
+    /// Returns an error if the known resources are not a valid path.
+    fn parse_known_resources(attributes: &String, known_str: &str) -> Result<Path> {
+        syn::parse_str(known_str).map_err(|err| {
+            Error::new_spanned(
+                attributes,
+                format!("Invalid known resources `{known_str}`: {err}"),
+            )
+        })
+    }
+
+    /// Parse the expiry date of the test.
+    ///
+    /// Date has the format `expires="YYYY-MM-DD"`
+    ///
+    /// Returns an error if the date is invalid.
+    fn parse_expires(date_str: &str) -> std::result::Result<NaiveDate, TestKindError> {
+        NaiveDate::parse_from_str(date_str, DATE_FORMAT).map_err(|err| {
+            TestKindError::InvalidDate(format!("Invalid expiry date format: {err:?}"))
+        })
+    }
+
+    /// Parse a list of platforms, given by its `key`.
+    ///
+    /// Platforms have the format `os="linux,macos"` or `arch="x86_64,aarch64"`
+    ///
+    /// Returns an error if any of the platforms are not in the `known` list.
+    fn parse_platforms(
+        attributes: &String,
+        key: &str,
+        known: &[&str],
+        platforms_str: &str,
+    ) -> Result<Vec<String>> {
+        let platforms: Vec<String> = platforms_str
+            .split(',')
+            .map(|s| s.trim().to_owned())
+            .collect();
+
+        let unknown_platforms: Vec<&String> = platforms
+            .iter()
+            .filter(|platform| !known.contains(&platform.as_str()))
+            .collect();
+        if !unknown_platforms.is_empty() {
+            return Err(Error::new_spanned(
+                attributes,
+                format!("Unknown `{key}`: {unknown_platforms:?}"),
+            ));
+        }
+
+        Ok(platforms)
+    }
+
+    /// Parse the priority of the test.
+    ///
+    /// Priority has the format `priority=1`, and may be negative.
+    ///
+    /// Returns an error if the priority is not a whole number.
+    fn parse_priority(attributes: &String, priority_str: &str) -> Result<i64> {
+        priority_str.parse().map_err(|_| {
+            Error::new_spanned(
+                attributes,
+                format!("Invalid priority `{priority_str}`, it must be a whole number"),
+            )
+        })
+    }
+
+    /// Parse how many times a flaky test is retried.
+    ///
+    /// Retries have the format `flaky=3`.
+    ///
+    /// Returns an error if the retries are not a positive whole number.
+    fn parse_flaky(attributes: &String, retries_str: &str) -> Result<u32> {
+        retries_str
+            .parse()
+            .ok()
+            .filter(|retries| *retries > 0)
+            .ok_or_else(|| {
+                Error::new_spanned(
+                    attributes,
+                    format!(
+                        "Invalid retries `flaky={retries_str}`, it must be a positive whole number"
+                    ),
+                )
+            })
+    }
+
+    /// Parse how long a test may run before it fails.
+    ///
+    /// Timeouts have the format `timeout="30s"`, as a whole number of milliseconds (`ms`),
+    /// seconds (`s`) or minutes (`m`).
+    ///
+    /// Returns an error if the timeout is not a positive whole number with a unit.
+    fn parse_timeout(attributes: &String, timeout_str: &str) -> Result<Duration> {
+        let timeout = timeout_str.trim();
+        let split = timeout
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(timeout.len());
+        let (count, unit) = timeout.split_at(split);
+        count
+            .parse::<u64>()
+            .ok()
+            .filter(|count| *count > 0)
+            .and_then(|count| match unit.trim() {
+                "ms" => Some(Duration::from_millis(count)),
+                "s" => Some(Duration::from_secs(count)),
+                "m" => count.checked_mul(60).map(Duration::from_secs),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                Error::new_spanned(
+                    attributes,
+                    format!(
+                        "Invalid timeout `timeout={timeout_str}`, it must be a positive whole number of milliseconds (`500ms`), seconds (`30s`) or minutes (`2m`)"
+                    ),
+                )
+            })
+    }
+
+    /// How long the test may run before it fails, if it has a timeout.
+    ///
+    /// Timeouts are ignored when `TEST_KIND_NO_TIMEOUT` is set.
+    #[must_use]
+    pub fn timeout(&self) -> Option<Duration> {
+        if *TEST_KIND_NO_TIMEOUT {
+            None
+        } else {
+            self.timeout
+        }
+    }
+
+    /// How many times the test is retried after it fails, before failing.
+    ///
+    /// Only flaky tests are retried, unless `TEST_KIND_NO_RETRY` is set.
+    #[must_use]
+    pub fn retries(&self) -> u32 {
+        if *TEST_KIND_NO_RETRY {
+            0
+        } else {
+            self.flaky.unwrap_or(0)
+        }
+    }
+
+    /// The attributes which run the test with the `harness`.
+    ///
+    /// When the test has a `harness_wasm`, it is used instead when compiled for `wasm32`,
+    /// and the `harness` otherwise, each with `#[cfg_attr]`.
+    #[must_use]
+    pub fn harness_attributes(&self, harness: &Path) -> Vec<Attribute> {
+        match &self.harness_wasm {
+            Some(harness_wasm) => vec![
+                parse_quote!(#[cfg_attr(target_arch = "wasm32", #harness_wasm)]),
+                parse_quote!(#[cfg_attr(not(target_arch = "wasm32"), #harness)]),
+            ],
+            None => vec![parse_quote!(#[#harness])],
+        }
+    }
+
+    /// Is the test a benchmark, so it is run by the benchmark harness?
+    #[must_use]
+    pub fn is_bench(&self) -> bool {
+        self.kind.is_bench()
+    }
+
+    /// Are panics in the test caught and reported, rather than failing it?
+    ///
+    /// Only quarantined tests are caught, when `TEST_KIND_QUARANTINE` is `report`.
+    #[must_use]
+    pub fn catches_panics(&self) -> bool {
+        self.quarantine && *TEST_KIND_QUARANTINE == QuarantineMode::Report
+    }
+
+    /// Parse the version of the crate the test was added in.
+    ///
+    /// Version has the format `since="1.4.0"`, and must be a semantic version.
+    ///
+    /// Returns an error if the version is not a semantic version.
+    fn parse_since(attributes: &String, since_str: &str) -> Result<String> {
+        if is_semver(since_str) {
+            Ok(since_str.to_owned())
+        } else {
+            Err(Error::new_spanned(
+                attributes,
+                format!("Invalid version `since={since_str}`, it must be a semantic version such as `1.4.0`"),
+            ))
+        }
+    }
+
+    /// Parse the cargo features the test requires.
+    ///
+    /// Features have the format `features="foo,bar"`.
+    ///
+    /// Returns an error if a feature name is empty or invalid, or a feature is given more than once.
+    fn parse_features(attributes: &String, features_str: &str) -> Result<Vec<String>> {
+        let features: Vec<String> = features_str
+            .split(',')
+            .map(|s| s.trim().to_owned())
+            .collect();
+
+        let invalid: Vec<&String> = features
+            .iter()
+            .filter(|feature| {
+                feature.is_empty()
+                    || !feature
+                        .chars()
+                        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '+' | '.'))
+            })
+            .collect();
+        if !invalid.is_empty() {
+            return Err(Error::new_spanned(
+                attributes,
+                format!("Invalid `features`: {invalid:?}"),
+            ));
+        }
+
+        let unique_set: HashSet<_> = features.iter().collect();
+        if features.len() != unique_set.len() {
+            return Err(Error::new_spanned(
+                attributes,
+                "Features may not be specified multiple times",
+            ));
+        }
+
+        Ok(features)
+    }
+
+    /// Parse the owners of the test.
+    ///
+    /// Owners have the format `owners="team-data,alice"`.
+    ///
+    /// Returns an error if any owner is empty.
+    fn parse_owners(attributes: &String, owners_str: &str) -> Result<Vec<String>> {
+        let owners: Vec<String> = owners_str.split(',').map(|s| s.trim().to_owned()).collect();
+        if owners.iter().any(String::is_empty) {
+            return Err(Error::new_spanned(
+                attributes,
+                format!("Invalid `owners={owners_str}`, every owner must be named"),
+            ));
+        }
+        Ok(owners)
+    }
+
+    /// The owners of the test, who are told when it is skipped.
+    #[must_use]
+    pub fn owners(&self) -> &[String] {
+        &self.owners
+    }
+
+    /// The cargo features which must all be enabled for the test to compile.
+    #[must_use]
+    pub fn features(&self) -> &[String] {
+        &self.features
+    }
+
+    /// The version of the crate the test was added in, if it is known.
+    #[must_use]
+    pub fn since(&self) -> Option<&str> {
+        self.since.as_deref()
+    }
+
+    /// The resources of the test which are not in `TEST_KIND_KNOWN_RESOURCES`, and so must
+    /// be in the `known_resources` registered in the code.
+    ///
+    /// Empty if the test has no `known_resources`.
+    #[must_use]
+    pub fn unregistered_resources(&self) -> Vec<String> {
+        if self.known_resources.is_none() {
+            return Vec::new();
+        }
+        self.kind
+            .resource_names()
+            .into_iter()
+            .filter(|resource| !is_test_resource_listed(resource))
+            .cloned()
+            .collect()
+    }
+
+    /// The name of the kind of test, multiple kinds are comma separated.
+    #[must_use]
+    pub fn kind_name(&self) -> String {
+        self.kind.name()
+    }
+
+    /// What to do with this particular test case?
+    ///
+    /// When `TEST_KIND_DRY_RUN` is set, every test is skipped with what would have been done.
+    /// When `TEST_KIND_RUN_ID` is set, it is added to the reason a test is skipped.
+    ///
+    /// Any warnings about the test are added to `warnings`.
+    pub fn what_to_do(self, warnings: &mut Vec<String>) -> TestSettings {
+        let kind = self.kind_name();
+        let settings = self.decide(warnings);
+        let settings = if *TEST_KIND_DRY_RUN {
+            dry_run(settings, &kind)
+        } else {
+            settings
+        };
+        with_run_id(settings, TEST_KIND_RUN_ID.as_deref())
+    }
+
+    /// What to do with this particular test case, ignoring `TEST_KIND_DRY_RUN`?
+    ///
+    /// Any warnings about the test are added to `warnings`.
+    fn decide(self, warnings: &mut Vec<String>) -> TestSettings {
+        // Forced tests always run, for debugging.
+        if *TEST_KIND_FORCE_RUN {
+            return TestSettings::Run;
+        }
+
+        // Expired tests fail regardless of their kind.
+        if let Some(expires) = self.expires {
+            if today() > expires {
+                return TestSettings::Fail {
+                    reason: format!(
+                        "Test expired on {expires}. Remove it, or extend its expiry date."
+                    ),
+                };
+            }
+        }
+
+        // Without a minimum priority, every test is important enough.
+        let min_priority = TEST_KIND_MIN_PRIORITY.unwrap_or(i64::MIN);
+        let settings = match self.kind.what_to_do(warnings) {
+            // Tests which would run are skipped on other platforms.
+            TestSettings::Run if !self.os.is_empty() && !self.os.iter().any(|os| os == OS) => {
+                TestSettings::skip(
+                    SkipCategory::Platform,
+                    format!("Test requires os {:?}, not {OS}", self.os),
+                )
+            }
+            TestSettings::Run
+                if !self.arch.is_empty() && !self.arch.iter().any(|arch| arch == ARCH) =>
+            {
+                TestSettings::skip(
+                    SkipCategory::Platform,
+                    format!("Test requires arch {:?}, not {ARCH}", self.arch),
+                )
+            }
+            // And skipped when they are not important enough.
+            TestSettings::Run if self.priority < min_priority => TestSettings::skip(
+                SkipCategory::Priority,
+                format!(
+                    "Test priority {} is below min priority {min_priority}",
+                    self.priority
+                ),
+            ),
+            // Or are not in the sample, when only some tests are run.
+            TestSettings::Run
+                if TEST_KIND_SAMPLE_PERCENT
+                    .zip(self.test_name.as_deref())
+                    .is_some_and(|(percent, name)| {
+                        !is_sampled(name, percent, &TEST_KIND_SAMPLE_SEED)
+                    }) =>
+            {
+                TestSettings::skip(SkipCategory::Sampled, "sampled out")
+            }
+            settings => settings,
+        };
+
+        // The custom reason is only given when the test is actually skipped.
+        let settings = match (settings, self.reason) {
+            (TestSettings::Skip { reason }, Some(custom)) => TestSettings::Skip {
+                reason: SkipReason::new(reason.category, format!("{custom} ({})", reason.text)),
+            },
+            (settings, _) => settings,
+        };
+        // So are its owners, so they can be told about it.
+        match settings {
+            TestSettings::Skip { reason } if !self.owners.is_empty() => TestSettings::Skip {
+                reason: SkipReason::new(
+                    reason.category,
+                    format!("{} [owners: {}]", reason.text, self.owners.join(", ")),
+                ),
+            },
+            settings => settings,
+        }
+    }
+}
+
+impl fmt::Display for AttributeKind {
+    /// Display the kind in its canonical form, such as `e2e, resources="db, redis"`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name().replace(',', ", "))?;
+        for (key, value) in self.options() {
+            write!(f, ", {key}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for AttributeKind {
+    type Err = Error;
+
+    /// Parse the kind of a test, as it is given to the `test_kind` macro, without the
+    /// options which every kind takes, such as `expires`.
+    ///
+    /// Returns an error if the kind is invalid. Like `TestAttributes`, `updated="git"` is invalid.
+    /// So are `resources@<profile>` lists, as only the resources of the active profile are kept,
+    /// so the kind could not be displayed as it was given.
+    fn from_str(attributes: &str) -> Result<Self> {
+        let attributes = &attributes.to_owned();
+        let (names, options) = parse_options(attributes);
+        combine_errors(duplicate_errors(attributes, &options))?;
+        if let Some(option) = options
+            .iter()
+            .find(|option| split_profile(&option.key).1.is_some())
+        {
+            return Err(Error::new_spanned(
+                attributes,
+                format!(
+                    "`{}` can not be parsed as an `AttributeKind`, as only the resources of the \
+                     active profile are kept",
+                    option.key
+                ),
+            ));
+        }
+        AttributeKind::from_parts(attributes, None, &names, &options, false)
+    }
+}
+
+impl FromStr for TestAttributes {
+    type Err = Error;
+
+    /// Convert the literal string parameters of the macro into `TestAttributes`.
+    ///
+    /// * `attributes`: The literal string
+    ///
+    /// Returns an error if the parameters are invalid.
+    /// `updated="git"` is invalid, as it needs the name of the test, see `for_test`.
+    fn from_str(attributes: &str) -> Result<Self> {
+        TestAttributes::parse_attributes(attributes, None)
+    }
+}
+
+impl TestAttributes {
+    /// The attributes of a test of the `kind`, without any of the options which every kind
+    /// takes, as it is given to `decision_for`.
+    pub(crate) fn from_kind(kind: AttributeKind) -> Self {
+        Self {
+            kind,
+            harness: None,
+            harness_wasm: None,
+            known_resources: None,
+            expires: None,
+            os: Vec::new(),
+            arch: Vec::new(),
+            reason: None,
+            priority: *TEST_KIND_DEFAULT_PRIORITY,
+            quarantine: false,
+            flaky: None,
+            timeout: None,
+            since: None,
+            features: Vec::new(),
+            owners: Vec::new(),
+            test_name: None,
+        }
+    }
+
+    /// Convert the literal string parameters of the macro into `TestAttributes`,
+    /// for the test function `test_name`.
+    ///
+    /// * `attributes`: The literal string
+    /// * `test_name`: The name of the test function, used to read `updated="git"`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parameters are invalid, such as if `updated="git"` and the env
+    /// var for `test_name` is not set.
+    pub fn for_test(attributes: &str, test_name: &str) -> Result<Self> {
+        TestAttributes::parse_attributes(attributes, Some(test_name))
+    }
+
+    fn parse_attributes(attributes: &str, test_name: Option<&str>) -> Result<Self> {
+        let attributes = &attributes.to_owned();
+        let (mut names, options) = parse_options(attributes);
+        let quarantine = names.iter().any(|name| name == "quarantine");
+        names.retain(|name| !FLAGS.contains(&name.as_str()));
+
+        let mut harness = None;
+        let mut harness_wasm = None;
+        let mut expires = None;
+        let mut os = Vec::new();
+        let mut arch = Vec::new();
+        let mut reason = None;
+        let mut priority = *TEST_KIND_DEFAULT_PRIORITY;
+        let mut flaky = None;
+        let mut timeout = None;
+        let mut since = None;
+        let mut features = Vec::new();
+        let mut owners = Vec::new();
+        let mut known_resources = None;
+        let mut kind_options = Vec::new();
+        let mut errors = duplicate_errors(attributes, &options);
+        for option in options {
+            let parsed = match option.key.as_str() {
+                "harness" => TestAttributes::parse_harness(attributes, &option.value)
+                    .map(|value| harness = Some(value)),
+                "harness_wasm" => TestAttributes::parse_harness(attributes, &option.value)
+                    .map(|value| harness_wasm = Some(value)),
+                "expires" => TestAttributes::parse_expires(&option.value)
+                    .map(|value| expires = Some(value))
+                    .map_err(|err| err.spanned(attributes)),
+                "os" => TestAttributes::parse_platforms(attributes, "os", KNOWN_OS, &option.value)
+                    .map(|value| os = value),
+                "arch" => {
+                    TestAttributes::parse_platforms(attributes, "arch", KNOWN_ARCH, &option.value)
+                        .map(|value| arch = value)
+                }
+                "priority" => TestAttributes::parse_priority(attributes, &option.value)
+                    .map(|value| priority = value),
+                "flaky" => TestAttributes::parse_flaky(attributes, &option.value)
+                    .map(|value| flaky = Some(value)),
+                "timeout" => TestAttributes::parse_timeout(attributes, &option.value)
+                    .map(|value| timeout = Some(value)),
+                "since" => TestAttributes::parse_since(attributes, &option.value)
+                    .map(|value| since = Some(value)),
+                "features" => TestAttributes::parse_features(attributes, &option.value)
+                    .map(|value| features = value),
+                "owners" => TestAttributes::parse_owners(attributes, &option.value)
+                    .map(|value| owners = value),
+                "known_resources" => {
+                    TestAttributes::parse_known_resources(attributes, &option.value)
+                        .map(|value| known_resources = Some(value))
+                }
+                "reason" => {
+                    reason = Some(option.value);
+                    Ok(())
+                }
+                _ => {
+                    kind_options.push(option);
+                    Ok(())
+                }
+            };
+            if let Err(err) = parsed {
+                errors.push(err);
+            }
+        }
+
+        // Every invalid option is reported, along with any errors in the kind.
+        let ((), kind) = both(
+            combine_errors(errors),
+            AttributeKind::from_parts(
+                attributes,
+                test_name,
+                &names,
+                &kind_options,
+                known_resources.is_some(),
+            ),
+        )?;
+
+        Ok(Self {
+            kind,
+            harness,
+            harness_wasm,
+            known_resources,
+            expires,
+            os,
+            arch,
+            reason,
+            priority,
+            quarantine,
+            flaky,
+            timeout,
+            since,
+            features,
+            owners,
+            test_name: test_name.map(ToOwned::to_owned),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        aged_decision, all_other_excluded, dry_run, env, excluded_reason,
+        excluded_resources_reason, expand_groups, is_sampled, is_semver, manual_decision,
+        resource_count_error, resources_decision, select_profile, split_resources, with_run_id,
+        AttributeKind, SkipCategory, TestAttributes, TestSettings, COMMON_OPTIONS,
+    };
+    use crate::config::MissingResourceMode;
+    use crate::error::TestKindError;
+    use crate::resource::ResourceReq;
+    use crate::unit_age::UnitAgeResult;
+    use chrono::NaiveDate;
+    use quote::quote;
+    use std::time::Duration;
+    use syn::{parse_quote, Path};
+
+    #[test]
+    fn updated_dates_are_allowed_up_to_the_future_tolerance() {
+        let today = NaiveDate::from_ymd_opt(2024, 2, 27).unwrap();
+        let tolerance = 3;
+        let max_date = AttributeKind::max_updated_date(today, tolerance);
+
+        let at_tolerance = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let after_tolerance = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+        assert!(at_tolerance <= max_date);
+        assert!(after_tolerance > max_date);
+        assert_eq!(AttributeKind::max_updated_date(today, 0), today);
+        assert_eq!(
+            AttributeKind::max_updated_date(today, u32::MAX),
+            NaiveDate::MAX
+        );
+    }
+
+    #[test]
+    fn priority_must_be_a_whole_number() {
+        let attributes = "integration, priority=x".to_owned();
+        assert_eq!(
+            TestAttributes::parse_priority(&attributes, "2").ok(),
+            Some(2)
+        );
+        assert_eq!(
+            TestAttributes::parse_priority(&attributes, "-1").ok(),
+            Some(-1)
+        );
+        assert!(TestAttributes::parse_priority(&attributes, "high").is_err());
+        assert!(TestAttributes::parse_priority(&attributes, "1.5").is_err());
+        assert!("integration, priority=high"
+            .parse::<TestAttributes>()
+            .is_err());
+    }
+
+    #[test]
+    fn integration_tests_can_not_have_resources() {
+        let err = "integration, resources=db"
+            .parse::<TestAttributes>()
+            .err()
+            .map(|err| err.to_string());
+        assert_eq!(
+            err.as_deref(),
+            Some("integration tests cannot declare resources; use a custom kind instead")
+        );
+        assert!("integration, e2e, resources=db"
+            .parse::<TestAttributes>()
+            .is_ok());
+    }
+
+    #[test]
+    fn unit_tests_can_not_require_resources() {
+        let err = |attributes: &str| {
+            attributes
+                .parse::<TestAttributes>()
+                .err()
+                .map(|err| err.to_string())
+        };
+        assert_eq!(
+            err("unit, updated=2024-01-01, resources=db").as_deref(),
+            Some("unit tests cannot require resources")
+        );
+        assert_eq!(
+            err("unit, updated=2024-01-01, resources@ci=db").as_deref(),
+            Some("unit tests cannot require resources")
+        );
+        assert!("unit, e2e, updated=2024-01-01, resources=db"
+            .parse::<TestAttributes>()
+            .is_ok());
+    }
+
+    #[test]
+    fn every_error_is_reported_at_once() {
+        let messages = |attributes: &str| -> Vec<String> {
+            attributes
+                .parse::<TestAttributes>()
+                .err()
+                .into_iter()
+                .flatten()
+                .map(|err| err.to_string())
+                .collect()
+        };
+
+        let messages_of_other = messages("api, resources=db, db, updated=yesterday");
+        assert_eq!(messages_of_other.len(), 2);
+        assert_eq!(
+            messages_of_other[0],
+            "Resources may not be specified multiple times"
+        );
+        assert!(messages_of_other[1].starts_with("Invalid date format"));
+
+        let messages_of_unit = messages("unit, updated=2000-01-01, created=never, os=plan9");
+        assert_eq!(messages_of_unit.len(), 3);
+        assert!(messages_of_unit[0].starts_with("Unknown `os`"));
+        assert!(messages_of_unit[1].starts_with("`updated=2000-01-01` must not be before"));
+        assert!(messages_of_unit[2].starts_with("Invalid date format"));
+    }
+
+    #[test]
+    fn since_must_be_a_semantic_version() {
+        assert!(is_semver("1.4.0"));
+        assert!(is_semver("0.10.20"));
+        assert!(is_semver("2.0.0-rc.1"));
+        assert!(is_semver("2.0.0-alpha-2+build.007"));
+        assert!(!is_semver("1.4"));
+        assert!(!is_semver("1.4.0.1"));
+        assert!(!is_semver("01.4.0"));
+        assert!(!is_semver("1.4.x"));
+        assert!(!is_semver("1.4.0-"));
+        assert!(!is_semver("1.4.0-rc.01"));
+        assert!(!is_semver("1.4.0+"));
+        assert!(!is_semver("v1.4.0"));
+
+        let attributes: Option<TestAttributes> = r#"integration, since="1.4.0""#.parse().ok();
+        assert_eq!(
+            attributes.as_ref().and_then(TestAttributes::since),
+            Some("1.4.0")
+        );
+        assert!(r#"integration, since="1.4""#.parse::<TestAttributes>().is_err());
+    }
+
+    #[test]
+    fn dry_runs_skip_tests_which_would_run() {
+        assert_eq!(
+            dry_run(TestSettings::Run, "unit"),
+            TestSettings::skip(SkipCategory::DryRun, "would run as unit")
+        );
+        assert_eq!(
+            dry_run(
+                TestSettings::skip(SkipCategory::Excluded, "Unit tests are excluded by `unit` in TEST_KIND_EXCLUDE"),
+                "unit"
+            ),
+            TestSettings::skip(SkipCategory::DryRun, "would skip anyway: [test_kind:excluded] Unit tests are excluded by `unit` in TEST_KIND_EXCLUDE")
+        );
+        assert_eq!(dry_run(TestSettings::Ignore, "unit"), TestSettings::Ignore);
+    }
+
+    #[test]
+    fn run_ids_are_added_to_skip_reasons() {
+        let excluded =
+            || TestSettings::skip(SkipCategory::Excluded, "Integration tests are excluded");
+        assert_eq!(
+            with_run_id(excluded(), Some("abc123")).reason().as_deref(),
+            Some("[test_kind:excluded] [run=abc123] Integration tests are excluded")
+        );
+        assert_eq!(with_run_id(excluded(), None), excluded());
+        assert_eq!(
+            with_run_id(TestSettings::Run, Some("abc123")),
+            TestSettings::Run
+        );
+        assert_eq!(
+            with_run_id(TestSettings::Ignore, Some("abc123")),
+            TestSettings::Ignore
+        );
+    }
+
+    #[test]
+    fn kinds_round_trip_through_their_canonical_form() {
+        let canonical = [
+            "unit, updated=2024-01-01",
+            "unit, updated=2024-01-01, created=2023-11-01, age=90, skip=7",
+            "integration",
+            "integration, updated=2024-01-01",
+            "bench",
+            "manual",
+            "e2e, resources=\"db\"",
+            "e2e, resources=\"postgres>=14, redis|valkey, !mock_server\", updated=2024-01-01",
+            "unit, e2e, resources=\"db\", updated=2024-01-01",
+        ];
+        for text in canonical {
+            let kind: AttributeKind = text.parse().unwrap();
+            assert_eq!(kind.to_string(), text);
+            assert_eq!(kind.to_string().parse::<AttributeKind>().ok(), Some(kind));
+        }
+
+        // Spacing and the order of options are normalised.
+        let kind: AttributeKind = "unit,skip = 7 ,  updated=2024 - 01 - 01".parse().unwrap();
+        assert_eq!(kind.to_string(), "unit, updated=2024-01-01, skip=7");
+        let kind: AttributeKind = "e2e, updated=\"2024-01-01\", resources=db redis"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            kind.to_string(),
+            "e2e, resources=\"db, redis\", updated=2024-01-01"
+        );
+
+        assert!("unit".parse::<AttributeKind>().is_err());
+        assert!("unit, updated=2024-01-01, os=linux"
+            .parse::<AttributeKind>()
+            .is_err());
+        assert!("e2e, resources=db, resources=db"
+            .parse::<AttributeKind>()
+            .is_err());
+        // The lists of other profiles would be lost, so they are not parsed.
+        assert!("e2e, resources=db, resources@staging=db-replica"
+            .parse::<AttributeKind>()
+            .is_err());
+        assert!("e2e, resources@staging=db"
+            .parse::<AttributeKind>()
+            .is_err());
+    }
+
+    #[test]
+    fn common_options_are_taken_by_every_kind() {
+        for key in COMMON_OPTIONS {
+            let attributes = format!("e2e, resources=db, {key}=\"x\"");
+            let messages: Vec<String> = attributes
+                .parse::<TestAttributes>()
+                .err()
+                .map(|err| err.into_iter().map(|err| err.to_string()).collect())
+                .unwrap_or_default();
+            assert!(
+                messages
+                    .iter()
+                    .all(|message| !message.starts_with("Invalid options")),
+                "{key}: {messages:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn wasm_harness_is_chosen_by_target_arch() {
+        let harness: Path = parse_quote!(test);
+        let attributes = |attributes: &str| {
+            let attributes: TestAttributes = attributes.parse().unwrap();
+            let harness_attributes = attributes.harness_attributes(&harness);
+            quote!(#(#harness_attributes)*).to_string()
+        };
+        assert_eq!(attributes("integration"), quote!(#[test]).to_string());
+        assert_eq!(
+            attributes(r#"integration, harness_wasm="wasm_bindgen_test::wasm_bindgen_test""#),
+            quote!(
+                #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+                #[cfg_attr(not(target_arch = "wasm32"), test)]
+            )
+            .to_string()
+        );
+        assert!(r#"integration, harness_wasm="not a path""#.parse::<TestAttributes>().is_err());
+    }
+
+    #[test]
+    fn quoted_resources_only_lose_their_outer_spaces() {
+        let attributes: Option<TestAttributes> = r#"e2e, resources=" db , redis ""#.parse().ok();
+        let names: Option<Vec<&String>> = attributes
+            .as_ref()
+            .map(|attributes| attributes.kind.resource_names());
+        assert_eq!(names, Some(vec![&"db".to_owned(), &"redis".to_owned()]));
+    }
+
+    #[test]
+    fn resources_can_be_separated_by_whitespace() {
+        let names = |attributes: &str| -> Vec<String> {
+            let attributes: TestAttributes = attributes.parse().unwrap();
+            attributes
+                .kind
+                .resource_names()
+                .into_iter()
+                .cloned()
+                .collect()
+        };
+        let expected = vec!["db".to_owned(), "redis".to_owned()];
+        assert_eq!(names(r#"e2e, resources="db redis""#), expected);
+        assert_eq!(names("e2e, resources=\"db  \t redis\""), expected);
+        assert_eq!(names("e2e, resources = db redis"), expected);
+        assert_eq!(names(r#"e2e, resources="db,redis""#), expected);
+        assert_eq!(names("e2e, resources = db, redis"), expected);
+        assert_eq!(
+            names(r#"e2e, resources="db redis, kafka""#),
+            ["db", "redis", "kafka"]
+        );
+        assert_eq!(
+            names("e2e, resources = postgres >= 14 ! mock_server - 2 redis | valkey"),
+            ["postgres", "mock_server-2", "redis", "valkey"]
+        );
+        assert_eq!(
+            split_resources("postgres >= 14 !mock_server-2, redis | valkey"),
+            ["postgres>=14", "!mock_server-2", "redis|valkey"]
+        );
+        assert_eq!(split_resources("db,, redis"), ["db", "", "redis"]);
+        assert!(r#"e2e, resources="db redis db""#.parse::<TestAttributes>().is_err());
+    }
+
+    #[test]
+    fn empty_and_invalid_resources_are_errors() {
+        let messages = |attributes: &str| -> Vec<String> {
+            attributes
+                .parse::<TestAttributes>()
+                .err()
+                .map(|err| err.into_iter().map(|err| err.to_string()).collect())
+                .unwrap_or_default()
+        };
+        assert_eq!(
+            messages("e2e, resources=\"db,,redis\""),
+            ["Empty resource in `resources=db,,redis`, remove the extra `,`"]
+        );
+        assert_eq!(
+            messages("e2e, resources=\"db,redis,\""),
+            ["Empty resource in `resources=db,redis,`, remove the extra `,`"]
+        );
+        assert_eq!(
+            messages("e2e, resources=\"db,re$dis\""),
+            ["Invalid resource name `re$dis`, it may only contain letters, numbers, `_` and `-`"]
+        );
+        assert_eq!(
+            messages("e2e, resources=\"db|\""),
+            ["Resource names can not be empty"]
+        );
+    }
+
+    #[test]
+    fn resource_counts_are_limited() {
+        assert_eq!(
+            resource_count_error("end2end", 1, Some(2), None).as_deref(),
+            Some("Tests of kind end2end must require at least 2 resources, not 1")
+        );
+        assert_eq!(resource_count_error("end2end", 2, Some(2), None), None);
+        assert_eq!(resource_count_error("end2end", 3, Some(2), None), None);
+        assert_eq!(resource_count_error("end2end", 3, None, Some(3)), None);
+        assert_eq!(
+            resource_count_error("end2end", 4, Some(2), Some(3)).as_deref(),
+            Some("Tests of kind end2end must require at most 3 resources, not 4")
+        );
+        assert_eq!(resource_count_error("end2end", 0, None, None), None);
+    }
+
+    #[test]
+    fn updated_dates_can_be_read_from_env_vars() {
+        env::set_var("TEST_KIND_TEST_UPDATED", " 2024-01-31 ");
+        env::set_var("TEST_KIND_TEST_UPDATED_INVALID", "last week");
+
+        assert_eq!(
+            AttributeKind::parse_updated("updated", "env:TEST_KIND_TEST_UPDATED", None).ok(),
+            NaiveDate::from_ymd_opt(2024, 1, 31)
+        );
+        assert!(AttributeKind::parse_updated(
+            "updated",
+            "env:TEST_KIND_TEST_UPDATED_INVALID",
+            None
+        )
+        .is_err());
+        assert_eq!(
+            AttributeKind::parse_updated("updated", "env:TEST_KIND_TEST_UNSET", None).err(),
+            Some(TestKindError::InvalidDate(
+                "`updated` is read from the env var `TEST_KIND_TEST_UNSET`, which is not set"
+                    .to_owned()
+            ))
+        );
+    }
+
+    #[test]
+    fn missing_resources_are_skipped_or_dropped() {
+        let missing = vec!["db".to_owned()];
+        let net = "net".to_owned();
+
+        assert_eq!(
+            resources_decision("e2e", &missing, &[], MissingResourceMode::Skip),
+            TestSettings::skip(
+                SkipCategory::MissingResources,
+                "Test of kind: e2e requires [\"db\"]"
+            )
+        );
+        assert_eq!(
+            resources_decision("e2e", &missing, &[], MissingResourceMode::Drop),
+            TestSettings::Ignore
+        );
+        assert_eq!(
+            resources_decision("e2e", &missing, &[&net], MissingResourceMode::Drop),
+            TestSettings::Ignore
+        );
+
+        // Only missing resources are dropped, forbidden ones are still skipped.
+        assert_eq!(
+            resources_decision("e2e", &[], &[&net], MissingResourceMode::Drop),
+            TestSettings::skip(
+                SkipCategory::ForbiddenResources,
+                "Test of kind: e2e forbids available [\"net\"]"
+            )
+        );
+        assert_eq!(
+            resources_decision("e2e", &[], &[], MissingResourceMode::Drop),
+            TestSettings::Run
+        );
+    }
+
+    #[test]
+    fn missing_resources_fail_when_required() {
+        let missing = vec!["db".to_owned()];
+        let net = "net".to_owned();
+
+        assert_eq!(
+            resources_decision("e2e", &missing, &[&net], MissingResourceMode::Fail),
+            TestSettings::Fail {
+                reason: "Test of kind: e2e requires [\"db\"], which must be available when resources are required".to_owned()
+            }
+        );
+        // Forbidden resources are still only skipped.
+        assert_eq!(
+            resources_decision("e2e", &[], &[&net], MissingResourceMode::Fail),
+            TestSettings::skip(
+                SkipCategory::ForbiddenResources,
+                "Test of kind: e2e forbids available [\"net\"]"
+            )
+        );
+        assert_eq!(
+            resources_decision("e2e", &[], &[], MissingResourceMode::Fail),
+            TestSettings::Run
+        );
+    }
+
+    #[test]
+    fn options_may_be_given_in_any_order() {
+        for attributes in [
+            "unit, updated=2024-01-01, created=2023-12-01, priority=1, reason=\"slow\"",
+            "unit, reason=\"slow\", created=2023-12-01, priority=1, updated=2024-01-01",
+            "api, updated=2024-01-01, os=linux, resources=db, since=\"1.0.0\"",
+            "api, since=\"1.0.0\", resources=db, os=linux, updated=2024-01-01",
+            "integration, priority=2, os=linux",
+        ] {
+            assert!(
+                attributes.parse::<TestAttributes>().is_ok(),
+                "{attributes} should parse"
+            );
+        }
+    }
+
+    #[test]
+    fn missing_and_unknown_options_are_reported_clearly() {
+        let messages = |attributes: &str| -> Vec<String> {
+            attributes
+                .parse::<TestAttributes>()
+                .err()
+                .into_iter()
+                .flatten()
+                .map(|err| err.to_string())
+                .collect()
+        };
+
+        assert_eq!(
+            messages("unit"),
+            ["Test kind 'unit' requires `updated=YYYY-MM-DD`"]
+        );
+        assert_eq!(
+            messages("unit, priority=1"),
+            ["Test kind 'unit' requires `updated=YYYY-MM-DD`"]
+        );
+        assert_eq!(
+            messages("api, updated=2024-01-01"),
+            ["Test kind 'api' requires `resources=<comma separated list of resources>`"]
+        );
+        assert_eq!(
+            messages("unit, updated=2024-01-01, limit=3"),
+            [format!(
+                "Invalid options for test kind 'unit': [\"limit\"], it takes [\"updated\", \"created\", \"age\", \"skip\"], \
+                 and every kind takes {COMMON_OPTIONS:?}"
+            )]
+        );
+        assert_eq!(
+            messages("unit, updatd=2024-01-01"),
+            [
+                format!(
+                    "Invalid options for test kind 'unit': [\"updatd\"], it takes [\"updated\", \"created\", \"age\", \"skip\"], \
+                     and every kind takes {COMMON_OPTIONS:?}"
+                ),
+                "Test kind 'unit' requires `updated=YYYY-MM-DD`".to_owned()
+            ]
+        );
+        assert_eq!(
+            messages("unit, api, updated=2024-01-01, resources=db, exipres=2030-01-01"),
+            [format!(
+                "Options not used by any of the test kinds: [\"exipres\"], they take \
+                 [\"updated\", \"created\", \"age\", \"skip\", \"resources\"], \
+                 and every kind takes {COMMON_OPTIONS:?}"
+            )]
+        );
+        assert_eq!(
+            messages("unit, updated=2024-01-01, updated=2024-01-02"),
+            ["`updated` may only be given once"]
+        );
+    }
+
+    #[test]
+    fn features_are_parsed() {
+        let attributes = String::new();
+        assert_eq!(
+            TestAttributes::parse_features(&attributes, "foo, bar-baz").ok(),
+            Some(vec!["foo".to_owned(), "bar-baz".to_owned()])
+        );
+        assert!(TestAttributes::parse_features(&attributes, "foo,").is_err());
+        assert!(TestAttributes::parse_features(&attributes, "foo bar").is_err());
+        assert!(TestAttributes::parse_features(&attributes, "foo,foo").is_err());
+
+        let parsed = "integration, features=\"foo,bar\"".parse::<TestAttributes>();
+        assert_eq!(
+            parsed.ok().map(|attributes| attributes.features().to_vec()),
+            Some(vec!["foo".to_owned(), "bar".to_owned()])
+        );
+    }
+
+    #[test]
+    fn dates_parse_with_or_without_spaces() {
+        let expected = NaiveDate::from_ymd_opt(2024, 1, 1);
+        for date in ["2024-01-01", "2024 - 01 - 01", "2024 -01- 01"] {
+            assert_eq!(
+                TestAttributes::parse_expires(date).ok(),
+                expected,
+                "{date} should parse"
+            );
+        }
+
+        assert!("unit, updated=\"2024-01-01\""
+            .parse::<TestAttributes>()
+            .is_ok());
+        assert!("unit, updated=2024 - 01 - 01"
+            .parse::<TestAttributes>()
+            .is_ok());
+        assert!("unit, updated=\"2024/01/01\""
+            .parse::<TestAttributes>()
+            .is_err());
+    }
+
+    #[test]
+    fn resources_may_have_versions() {
+        let names = |attributes: &str| {
+            attributes.parse::<TestAttributes>().ok().map(|attributes| {
+                attributes
+                    .kind
+                    .resource_names()
+                    .into_iter()
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        assert_eq!(
+            names("db, resources=postgres>=14, redis"),
+            Some(vec!["postgres".to_owned(), "redis".to_owned()])
+        );
+        assert_eq!(
+            names("db, resources=redis, postgres >= 14, mysql > 8"),
+            Some(vec![
+                "redis".to_owned(),
+                "postgres".to_owned(),
+                "mysql".to_owned()
+            ])
+        );
+        assert_eq!(
+            names("db, resources=\"redis, postgres=16\""),
+            Some(vec!["redis".to_owned(), "postgres".to_owned()])
+        );
+        assert!("db, resources=postgres>=latest"
+            .parse::<TestAttributes>()
+            .is_err());
+    }
+
+    #[test]
+    fn tests_requiring_excluded_resources_are_skipped() {
+        let resources = |resources: &str| -> Vec<ResourceReq> {
+            resources
+                .split(',')
+                .map(|resource| ResourceReq::parse(resource).unwrap())
+                .collect()
+        };
+        let gpu = |name: &str| name == "gpu";
+
+        assert_eq!(
+            excluded_resources_reason(&resources("gpu"), gpu)
+                .map(|reason| reason.to_string())
+                .as_deref(),
+            Some("[test_kind:excluded_resources] resource gpu excluded")
+        );
+        assert_eq!(
+            excluded_resources_reason(&resources("db, gpu, redis"), gpu)
+                .map(|reason| reason.to_string())
+                .as_deref(),
+            Some("[test_kind:excluded_resources] resource gpu excluded")
+        );
+        assert_eq!(
+            excluded_resources_reason(&resources("gpu, tpu"), |name| name.ends_with("pu"))
+                .map(|reason| reason.to_string())
+                .as_deref(),
+            Some("[test_kind:excluded_resources] resources gpu, tpu excluded")
+        );
+        assert_eq!(
+            excluded_resources_reason(&resources("cpu|gpu"), gpu)
+                .map(|reason| reason.to_string())
+                .as_deref(),
+            Some("[test_kind:excluded_resources] resource gpu excluded")
+        );
+        assert_eq!(
+            excluded_resources_reason(&resources("db, redis"), gpu),
+            None
+        );
+        assert_eq!(excluded_resources_reason(&resources("db, !gpu"), gpu), None);
+    }
+
+    #[test]
+    fn declared_resource_groups_are_expanded() {
+        let groups = vec![
+            (
+                "ci-full".to_owned(),
+                vec!["db".to_owned(), "queues".to_owned()],
+            ),
+            (
+                "queues".to_owned(),
+                vec!["redis".to_owned(), "kafka".to_owned()],
+            ),
+        ];
+        let expand = |resources: &str| -> Vec<String> {
+            let resources: Vec<ResourceReq> = resources
+                .split(',')
+                .map(|resource| ResourceReq::parse(resource).unwrap())
+                .collect();
+            expand_groups(&resources, &groups)
+                .unwrap()
+                .iter()
+                .map(ToString::to_string)
+                .collect()
+        };
+
+        assert_eq!(expand("ci-full"), ["db", "redis", "kafka"]);
+        assert_eq!(expand("s3, queues"), ["s3", "redis", "kafka"]);
+        // Resources required directly and through a group are only required once.
+        assert_eq!(expand("db, ci-full"), ["db", "redis", "kafka"]);
+        // Only single required resources are expanded.
+        assert_eq!(expand("queues|db, !ci-full"), ["queues|db", "ci-full"]);
+        assert_eq!(expand("queues>=2"), ["queues>=2"]);
+
+        let groups = vec![("bad".to_owned(), vec!["db>=latest".to_owned()])];
+        let resources = [ResourceReq::parse("bad").unwrap()];
+        assert!(expand_groups(&resources, &groups).is_err());
+    }
+
+    #[test]
+    fn tests_with_their_own_ignore_are_reported_as_skipped() {
+        assert_eq!(
+            TestSettings::Run.ignored_by_test(Some("manual")),
+            TestSettings::skip(
+                SkipCategory::Ignored,
+                "Ignored by its own `#[ignore]`: manual"
+            )
+        );
+        assert_eq!(
+            TestSettings::Run.ignored_by_test(None),
+            TestSettings::skip(SkipCategory::Ignored, "Ignored by its own `#[ignore]`")
+        );
+        let skip = TestSettings::skip(
+            SkipCategory::Excluded,
+            "Test of kind: e2e are excluded by `e2e` in TEST_KIND_EXCLUDE",
+        );
+        assert_eq!(skip.ignored_by_test(Some("manual")), skip);
+        assert_eq!(
+            TestSettings::Ignore.ignored_by_test(None),
+            TestSettings::Ignore
+        );
+    }
+
+    #[test]
+    fn unit_tests_may_override_their_age() {
+        let kind = |attributes: &str| attributes.parse::<TestAttributes>().unwrap().kind;
+
+        assert!(matches!(
+            kind("unit, updated=2024-01-01, age=90, skip=7"),
+            AttributeKind::Unit {
+                age: Some(90),
+                skip: Some(7),
+                ..
+            }
+        ));
+        assert!(matches!(
+            kind("unit, skip=7, updated=2024-01-01"),
+            AttributeKind::Unit {
+                age: None,
+                skip: Some(7),
+                ..
+            }
+        ));
+        assert!(matches!(
+            kind("unit, updated=2024-01-01"),
+            AttributeKind::Unit {
+                age: None,
+                skip: None,
+                ..
+            }
+        ));
+
+        let err = "unit, updated=2024-01-01, age=ninety, skip=-1"
+            .parse::<TestAttributes>()
+            .err()
+            .unwrap();
+        let messages: Vec<String> = err.into_iter().map(|err| err.to_string()).collect();
+        assert_eq!(
+            messages,
+            [
+                "`age=ninety` is invalid, it must be a whole number of days",
+                "`skip=-1` is invalid, it must be a whole number of days",
+            ]
+        );
+        assert!("api, resources=db, age=90"
+            .parse::<TestAttributes>()
+            .is_err());
+    }
+
+    #[test]
+    fn duplicated_options_are_errors() {
+        let messages = |attributes: &str| -> Vec<String> {
+            attributes
+                .parse::<TestAttributes>()
+                .err()
+                .into_iter()
+                .flatten()
+                .map(|err| err.to_string())
+                .collect()
+        };
+
+        assert_eq!(
+            messages("unit, updated=2024-01-01, updated=2025-01-01"),
+            ["`updated` may only be given once"]
+        );
+        assert_eq!(
+            messages("api, resources=db, resources=db"),
+            ["Resources may not be specified multiple times"]
+        );
+        assert_eq!(
+            messages("integration, priority=1, os=linux, priority=2"),
+            ["`priority` may only be given once"]
+        );
+        assert_eq!(
+            messages("unit, api, resources=db, updated=2024-01-01, updated=2024-01-02"),
+            ["`updated` may only be given once"]
+        );
+        assert_eq!(
+            messages("api, reason=\"a\", resources=db, reason=\"b\", resources=db"),
+            [
+                "`reason` may only be given once",
+                "Resources may not be specified multiple times"
+            ]
+        );
+    }
+
+    #[test]
+    fn benchmarks_take_no_options_and_never_age() {
+        let attributes = "bench".parse::<TestAttributes>().unwrap();
+        assert!(attributes.is_bench());
+        assert_eq!(attributes.kind_name(), "bench");
+        assert_eq!(attributes.what_to_do(&mut Vec::new()), TestSettings::Run);
+
+        assert!("bench, e2e, resources=db"
+            .parse::<TestAttributes>()
+            .unwrap()
+            .is_bench());
+        assert!(!"integration".parse::<TestAttributes>().unwrap().is_bench());
+        assert!("bench, updated=2023-10-10"
+            .parse::<TestAttributes>()
+            .is_err());
+    }
+
+    #[test]
+    fn resources_are_chosen_by_profile() {
+        let profiles = || vec![("staging", "db"), ("prod", "db-replica")];
+
+        assert_eq!(
+            select_profile(Some("local-db"), profiles(), Some("prod")),
+            Ok(Some("db-replica"))
+        );
+        assert_eq!(
+            select_profile(Some("local-db"), profiles(), Some("STAGING")),
+            Ok(Some("db"))
+        );
+        assert_eq!(
+            select_profile(Some("local-db"), profiles(), Some("dev")),
+            Ok(Some("local-db"))
+        );
+        assert_eq!(
+            select_profile(Some("local-db"), profiles(), None),
+            Ok(Some("local-db"))
+        );
+        assert_eq!(
+            select_profile::<&str>(None, Vec::new(), Some("dev")),
+            Ok(None)
+        );
+        assert_eq!(
+            select_profile(None, profiles(), Some("dev")),
+            Err("Resources are only given for the profiles [\"staging\", \"prod\"], but `TEST_KIND_PROFILE` is `dev`".to_owned())
+        );
+        assert_eq!(
+            select_profile(None, profiles(), None),
+            Err("Resources are only given for the profiles [\"staging\", \"prod\"], but `TEST_KIND_PROFILE` is not set".to_owned())
+        );
+    }
+
+    #[test]
+    fn resources_may_be_given_for_profiles() {
+        assert!("e2e, resources=db, resources@staging=db-replica"
+            .parse::<TestAttributes>()
+            .is_ok());
+        let err = "e2e, resources @ staging = db, resources@prod=db-replica"
+            .parse::<TestAttributes>()
+            .err()
+            .map(|err| err.to_string());
+        assert_eq!(
+            err.as_deref(),
+            Some("Resources are only given for the profiles [\"staging\", \"prod\"], but `TEST_KIND_PROFILE` is not set")
+        );
+        assert!(
+            "unit, e2e, updated=2024-01-01, resources=db, resources@prod=db-replica"
+                .parse::<TestAttributes>()
+                .is_ok()
+        );
+        assert!("e2e, resources=db, updated@prod=2024-01-01"
+            .parse::<TestAttributes>()
+            .is_err());
+        assert!("e2e, resources=db, resources@=db"
+            .parse::<TestAttributes>()
+            .is_err());
+        assert!("e2e, resources=db, resources@prod=db, resources@prod=redis"
+            .parse::<TestAttributes>()
+            .is_ok());
+        assert!("e2e, resources=db, resources@prod=db, resources@prod=db"
+            .parse::<TestAttributes>()
+            .is_err());
+    }
+
+    #[test]
+    fn repeated_resources_are_accumulated() {
+        let resources = |attributes: &str| match attributes.parse::<TestAttributes>() {
+            Ok(TestAttributes {
+                kind: AttributeKind::Other { resources, .. },
+                ..
+            }) => Ok(resources
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()),
+            Ok(_) => Err("not an other kind".to_owned()),
+            Err(err) => Err(err.to_string()),
+        };
+
+        assert_eq!(
+            resources("e2e, resources=db, resources=redis"),
+            Ok(vec!["db".to_owned(), "redis".to_owned()])
+        );
+        assert_eq!(
+            resources("e2e, resources=db, cache, updated=2024-01-01, resources=redis, queue"),
+            Ok(vec![
+                "db".to_owned(),
+                "cache".to_owned(),
+                "redis".to_owned(),
+                "queue".to_owned()
+            ])
+        );
+        assert_eq!(
+            resources("e2e, resources=db, resources=redis, db"),
+            Err("Resources may not be specified multiple times".to_owned())
+        );
+    }
+
+    #[test]
+    fn future_updated_dates_are_clamped_when_allowed() {
+        let today = NaiveDate::from_ymd_opt(2024, 2, 27).unwrap();
+        let max_date = AttributeKind::max_updated_date(today, 2);
+        let tomorrow = NaiveDate::from_ymd_opt(2024, 2, 28).unwrap();
+        let next_year = NaiveDate::from_ymd_opt(2025, 2, 27).unwrap();
+        let last_year = NaiveDate::from_ymd_opt(2023, 2, 27).unwrap();
+        let updated = |date, allow_future| {
+            AttributeKind::future_updated_date(date, today, max_date, allow_future)
+        };
+
+        assert_eq!(updated(tomorrow, false), Some(tomorrow));
+        assert_eq!(updated(next_year, false), None);
+        assert_eq!(updated(last_year, false), Some(last_year));
+        assert_eq!(updated(tomorrow, true), Some(today));
+        assert_eq!(updated(next_year, true), Some(today));
+        assert_eq!(updated(last_year, true), Some(last_year));
+    }
+
+    #[test]
+    fn clamped_dates_age_as_freshly_updated() {
+        let today = super::today();
+        let max_date = AttributeKind::max_updated_date(today, 0);
+        let next_year = today + chrono::Days::new(365);
+        let updated = AttributeKind::future_updated_date(next_year, today, max_date, true).unwrap();
+        let unit = AttributeKind::Unit {
+            updated,
+            created: None,
+            age: Some(1),
+            skip: Some(0),
+        };
+        assert_eq!(unit.what_to_do(&mut Vec::new()), TestSettings::Run);
+    }
+
+    #[test]
+    fn owners_are_parsed() {
+        let attributes = String::new();
+        assert_eq!(
+            TestAttributes::parse_owners(&attributes, "team-data, alice").ok(),
+            Some(vec!["team-data".to_owned(), "alice".to_owned()])
+        );
+        assert!(TestAttributes::parse_owners(&attributes, "team-data,,alice").is_err());
+        assert!(TestAttributes::parse_owners(&attributes, "alice,").is_err());
+        assert!(TestAttributes::parse_owners(&attributes, " ").is_err());
+    }
+
+    #[test]
+    fn owners_are_given_when_skipped() {
+        let settings = |attributes: &str| {
+            attributes
+                .parse::<TestAttributes>()
+                .unwrap()
+                .what_to_do(&mut Vec::new())
+        };
+
+        assert_eq!(
+            settings("integration, os=redox, reason=\"old\", owners=\"team-data,alice\""),
+            TestSettings::skip(
+                SkipCategory::Platform,
+                format!(
+                    "old (Test requires os [\"redox\"], not {}) [owners: team-data, alice]",
+                    std::env::consts::OS
+                )
+            )
+        );
+        assert_eq!(
+            settings("integration, owners=\"team-data\""),
+            TestSettings::Run
+        );
+        assert_eq!(
+            "integration, owners=\"team-data, alice\""
+                .parse::<TestAttributes>()
+                .unwrap()
+                .owners(),
+            ["team-data", "alice"]
+        );
+    }
+
+    #[test]
+    fn excluded_reasons_name_what_excluded_them() {
+        assert_eq!(
+            excluded_reason("Unit tests", "unit").to_string(),
+            "[test_kind:excluded] Unit tests are excluded by `unit` in TEST_KIND_EXCLUDE"
+        );
+        assert_eq!(
+            excluded_reason("Integration tests", "integ*").to_string(),
+            "[test_kind:excluded] Integration tests are excluded by `integ*` in TEST_KIND_EXCLUDE"
+        );
+        assert_eq!(
+            excluded_reason("Test of kind: api-v1", "api-*").to_string(),
+            "[test_kind:excluded] Test of kind: api-v1 are excluded by `api-*` in TEST_KIND_EXCLUDE"
+        );
+    }
+
+    #[test]
+    fn skip_reasons_start_with_their_category() {
+        let reason = |settings: TestSettings| settings.reason().unwrap();
+        let categories = [
+            (SkipCategory::Excluded, "[test_kind:excluded] "),
+            (SkipCategory::NotIncluded, "[test_kind:not_included] "),
+            (
+                SkipCategory::ExcludedResources,
+                "[test_kind:excluded_resources] ",
+            ),
+            (
+                SkipCategory::MissingResources,
+                "[test_kind:missing_resources] ",
+            ),
+            (
+                SkipCategory::ForbiddenResources,
+                "[test_kind:forbidden_resources] ",
+            ),
+            (SkipCategory::Aged, "[test_kind:aged] "),
+            (SkipCategory::Platform, "[test_kind:platform] "),
+            (SkipCategory::Priority, "[test_kind:priority] "),
+            (SkipCategory::Sampled, "[test_kind:sampled] "),
+            (SkipCategory::Ignored, "[test_kind:ignored] "),
+            (SkipCategory::DryRun, "[test_kind:dry_run] "),
+            (SkipCategory::Manual, "[test_kind:manual] "),
+            (SkipCategory::Multiple, "[test_kind:multiple] "),
+            (SkipCategory::Runtime, "[test_kind:runtime] "),
+        ];
+        for (category, prefix) in categories {
+            assert_eq!(
+                reason(TestSettings::skip(category, "why")),
+                format!("{prefix}why")
+            );
+        }
+
+        let settings = |attributes: &str| {
+            attributes
+                .parse::<TestAttributes>()
+                .unwrap()
+                .what_to_do(&mut Vec::new())
+        };
+        assert!(reason(settings("integration, os=redox, reason=\"old\""))
+            .starts_with("[test_kind:platform] old (Test requires os"));
+        assert!(
+            reason(settings("unit, updated=2024-01-01, age=1, skip=100000"))
+                .starts_with("[test_kind:aged] ")
+        );
+        assert!(reason(TestSettings::Run.ignored_by_test(None)).starts_with("[test_kind:ignored] "));
+    }
+
+    #[test]
+    fn flaky_retries_must_be_a_positive_whole_number() {
+        let attributes = "integration, flaky=x".to_owned();
+        assert_eq!(TestAttributes::parse_flaky(&attributes, "3").ok(), Some(3));
+        assert!(TestAttributes::parse_flaky(&attributes, "0").is_err());
+        assert!(TestAttributes::parse_flaky(&attributes, "-1").is_err());
+        assert!(TestAttributes::parse_flaky(&attributes, "many").is_err());
+        assert!("integration, flaky=0".parse::<TestAttributes>().is_err());
+
+        let retries = |attributes: &str| attributes.parse::<TestAttributes>().unwrap().retries();
+        assert_eq!(retries("integration, flaky=3"), 3);
+        assert_eq!(retries("integration"), 0);
+    }
+
+    #[test]
+    fn manual_tests_are_skipped_unless_asked_for() {
+        let attributes = "manual".parse::<TestAttributes>().unwrap();
+        assert_eq!(attributes.kind_name(), "manual");
+        assert_eq!(
+            attributes.what_to_do(&mut Vec::new()),
+            TestSettings::skip(SkipCategory::Manual, "manual test, run explicitly")
+        );
+        assert_eq!(manual_decision(true), TestSettings::Run);
+
+        assert!("manual, resources=db".parse::<TestAttributes>().is_err());
+        assert!("manual, updated=2024-01-01"
+            .parse::<TestAttributes>()
+            .is_err());
+    }
+
+    #[test]
+    fn errors_are_categorised() {
+        let is_invalid_date = |result: std::result::Result<NaiveDate, TestKindError>| {
+            matches!(result, Err(TestKindError::InvalidDate(_)))
+        };
+        assert!(is_invalid_date(AttributeKind::parse_updated(
+            "updated",
+            "2024-13-01",
+            None
+        )));
+        assert!(is_invalid_date(AttributeKind::parse_updated(
+            "updated",
+            "2000-01-01",
+            None
+        )));
+        assert!(is_invalid_date(AttributeKind::parse_updated(
+            "updated",
+            "2999-01-01",
+            None
+        )));
+        assert!(is_invalid_date(AttributeKind::parse_updated(
+            "updated", "9x", None
+        )));
+        assert!(is_invalid_date(TestAttributes::parse_expires("soon")));
+
+        let errors = |resources: &str| AttributeKind::check_resources("e2e", resources, false).1;
+        assert_eq!(errors("db, redis"), []);
+        assert_eq!(errors("db, redis, db"), [TestKindError::DuplicateResource]);
+        assert!(matches!(
+            errors("db,,redis").as_slice(),
+            [TestKindError::InvalidResources(_)]
+        ));
+        assert!(matches!(
+            errors("my$db").as_slice(),
+            [TestKindError::InvalidResources(_)]
+        ));
+
+        let messages = |attributes: &str| -> Vec<String> {
+            attributes
+                .parse::<TestAttributes>()
+                .err()
+                .map(|err| err.into_iter().map(|err| err.to_string()).collect())
+                .unwrap_or_default()
+        };
+        assert_eq!(
+            messages("e2e, api, e2e, resources=db"),
+            [TestKindError::DuplicateKind.to_string()]
+        );
+    }
+
+    #[test]
+    fn updated_dates_can_be_read_from_git() {
+        env::set_var("TEST_KIND_GIT_DATE_MY_GIT_TEST", "2024-02-29");
+
+        assert_eq!(
+            AttributeKind::parse_updated("updated", "git", Some("my_git_test")).ok(),
+            NaiveDate::from_ymd_opt(2024, 2, 29)
+        );
+        assert_eq!(
+            AttributeKind::parse_updated("updated", "git", Some("r#my_git_test")).ok(),
+            NaiveDate::from_ymd_opt(2024, 2, 29)
+        );
+        assert!(TestAttributes::for_test(r#"unit, updated="git""#, "my_git_test").is_ok());
+        assert!(r#"unit, updated="git""#.parse::<TestAttributes>().is_err());
+        assert!(AttributeKind::parse_updated("created", "git", Some("my_git_test")).is_err());
+        assert_eq!(
+            AttributeKind::parse_updated("updated", "git", Some("unset_git_test")).err(),
+            Some(TestKindError::InvalidDate(
+                "`updated=git` is read from the env var `TEST_KIND_GIT_DATE_UNSET_GIT_TEST`, \
+                 which is not set. Set it from a build script with \
+                 `cargo:rustc-env=TEST_KIND_GIT_DATE_UNSET_GIT_TEST=YYYY-MM-DD`"
+                    .to_owned()
+            ))
+        );
+    }
+
+    #[test]
+    fn all_other_kinds_can_be_excluded_at_once() {
+        let kind = |attributes: &str| attributes.parse::<TestAttributes>().unwrap().kind;
+        let excluded = Some(TestSettings::skip(
+            SkipCategory::Excluded,
+            "all external-resource tests excluded",
+        ));
+
+        for attributes in [
+            r#"e2e, resources="db""#,
+            r#"end2end, resources="net|proxy""#,
+            r#"gpu, resources="cuda>=12", updated="2024-01-01""#,
+        ] {
+            assert_eq!(all_other_excluded(true, &kind(attributes)), excluded);
+            assert_eq!(all_other_excluded(false, &kind(attributes)), None);
+        }
+        for attributes in [r#"unit, updated="2024-01-01""#, "integration"] {
+            assert_eq!(all_other_excluded(true, &kind(attributes)), None);
+        }
+    }
+
+    #[test]
+    fn timeouts_have_a_unit() {
+        let attributes = "integration, timeout=x".to_owned();
+        let timeout = |timeout| TestAttributes::parse_timeout(&attributes, timeout).ok();
+        assert_eq!(timeout("30s"), Some(Duration::from_secs(30)));
+        assert_eq!(timeout(" 500ms "), Some(Duration::from_millis(500)));
+        assert_eq!(timeout("2m"), Some(Duration::from_secs(120)));
+        assert_eq!(timeout("0s"), None);
+        assert_eq!(timeout("30"), None);
+        assert_eq!(timeout("1.5s"), None);
+        assert_eq!(timeout("-1s"), None);
+        assert_eq!(timeout("1h"), None);
+        assert_eq!(
+            timeout("99999999999999999999m"),
+            None,
+            "an overflow is invalid, not a panic"
+        );
+        assert!(r#"integration, timeout="soon""#.parse::<TestAttributes>().is_err());
+    }
+
+    #[test]
+    fn integration_tests_may_have_an_updated_date() {
+        let kind = |attributes: &str| attributes.parse::<TestAttributes>().map(|a| a.kind);
+        assert!(matches!(
+            kind("integration"),
+            Ok(AttributeKind::Integration { updated: None })
+        ));
+        assert!(matches!(
+            kind(r#"integration, updated="2024-01-01""#),
+            Ok(AttributeKind::Integration { updated }) if updated == NaiveDate::from_ymd_opt(2024, 1, 1)
+        ));
+        assert!(kind(r#"integration, updated="yesterday""#).is_err());
+        assert!(kind("integration, created=2024-01-01").is_err());
+    }
+
+    #[test]
+    fn integration_tests_age_out_like_other_kinds() {
+        let mut warnings = Vec::new();
+        let mut decide =
+            |age| aged_decision(age, "Integration test", &mut warnings, || TestSettings::Run);
+
+        assert_eq!(decide(UnitAgeResult::Young), TestSettings::Run);
+        assert_eq!(
+            decide(UnitAgeResult::Warn("Skipped in 3 days".to_owned())),
+            TestSettings::Run
+        );
+        assert_eq!(
+            decide(UnitAgeResult::Aged("Silenced in 5 days".to_owned())),
+            TestSettings::skip(SkipCategory::Aged, "Silenced in 5 days")
+        );
+        assert_eq!(decide(UnitAgeResult::Old), TestSettings::Ignore);
+        assert_eq!(
+            warnings,
+            ["Integration test is aging. Skipped in 3 days".to_owned()]
+        );
+
+        // Only tests which run warn that they are aging.
+        let mut warnings = Vec::new();
+        let excluded = TestSettings::skip(SkipCategory::Excluded, "excluded");
+        assert_eq!(
+            aged_decision(
+                UnitAgeResult::Warn("Skipped in 3 days".to_owned()),
+                "Integration test",
+                &mut warnings,
+                || excluded.clone()
+            ),
+            excluded
+        );
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn samples_are_chosen_by_the_seed() {
+        let names: Vec<String> = (0..1000).map(|index| format!("test_{index}")).collect();
+        let sample = |percent, seed| -> Vec<&str> {
+            names
+                .iter()
+                .map(String::as_str)
+                .filter(|name| is_sampled(name, percent, seed))
+                .collect()
+        };
+
+        // The same seed always gives the same sample.
+        assert_eq!(sample(10, "shard-1"), sample(10, "shard-1"));
+        assert_ne!(sample(10, "shard-1"), sample(10, "shard-2"));
+        // Which is stable across runs and platforms.
+        assert!(is_sampled("test_8", 10, "shard-1"));
+        assert!(!is_sampled("test_0", 10, "shard-1"));
+        // And is about the percentage of the tests.
+        assert!((70..130).contains(&sample(10, "shard-1").len()));
+        // A larger sample includes every test in a smaller one.
+        let larger = sample(50, "shard-1");
+        assert!(sample(10, "shard-1")
+            .iter()
+            .all(|name| larger.contains(name)));
+        assert!(sample(0, "shard-1").is_empty());
+        assert_eq!(sample(100, "shard-1").len(), names.len());
+    }
+}
@@ -0,0 +1,1612 @@
+//! Configuration control for the `test_kind` maro.
+//!
+use chrono::{Datelike, Local, NaiveDate, Weekday};
+use lazy_static::lazy_static;
+use std::collections::HashSet;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+
+use crate::attribute_kind::TestSettings;
+use crate::report::json_string;
+use crate::resource::{check_available_name, ResourceReq};
+use crate::unit_age::{days_from_env, UnitAge};
+
+lazy_static! {
+    static ref TEST_KIND_EXCLUDE: Vec<String> = read_env_var_list("TEST_KIND_EXCLUDE");
+    static ref TEST_KIND_INCLUDE: Vec<String> = read_env_var_list("TEST_KIND_INCLUDE");
+    static ref TEST_KIND_EXCLUDE_RESOURCE: Vec<String> =
+        read_env_var_list("TEST_KIND_EXCLUDE_RESOURCE");
+    pub(crate) static ref TEST_KIND_UNIT_AGE: UnitAge = UnitAge::from_env();
+    static ref TEST_KIND_OTHER_AGES: Vec<(String, UnitAge)> = UnitAge::other_kinds_from_env();
+    pub(crate) static ref TEST_KIND_INTEGRATION_AGE: Option<UnitAge> =
+        UnitAge::integration_from_env();
+    static ref TEST_KIND_KNOWN_RESOURCES: Vec<String> =
+        read_env_var_list("TEST_KIND_KNOWN_RESOURCES");
+    static ref TEST_KIND_RESOURCES: Vec<String> = read_available_resources();
+    static ref TEST_KIND_RESOURCE_GROUPS: Vec<(String, Vec<String>)> =
+        read_resource_groups(&read_env_var_list("TEST_KIND_RESOURCE_GROUPS"));
+    static ref TEST_KIND_AVAILABLE_RESOURCES: HashSet<String> =
+        expand_resources(&TEST_KIND_RESOURCES, &TEST_KIND_RESOURCE_GROUPS);
+    static ref TEST_KIND_DEFINED: Vec<String> = read_env_var_list("TEST_KIND_DEFINED");
+    static ref TEST_KIND_MIN_RESOURCES: Vec<(String, usize)> =
+        read_kind_counts(env::vars(), "TEST_KIND_MIN_RESOURCES_")
+            .unwrap_or_else(|err| panic!("{err}"));
+    static ref TEST_KIND_MAX_RESOURCES: Vec<(String, usize)> =
+        read_kind_counts(env::vars(), "TEST_KIND_MAX_RESOURCES_")
+            .unwrap_or_else(|err| panic!("{err}"));
+    pub(crate) static ref TEST_KIND_MIN_UPDATED: Result<NaiveDate, String> =
+        read_min_updated(env::var("TEST_KIND_MIN_UPDATED").ok().as_deref());
+    pub(crate) static ref TEST_KIND_FUTURE_TOLERANCE_DAYS: u32 =
+        days_from_env("TEST_KIND_FUTURE_TOLERANCE_DAYS", 2);
+    pub(crate) static ref TEST_KIND_MIN_PRIORITY: Option<i64> =
+        priority_from_env("TEST_KIND_MIN_PRIORITY");
+    pub(crate) static ref TEST_KIND_SAMPLE_PERCENT: Option<u32> =
+        read_sample_percent(env::var("TEST_KIND_SAMPLE_PERCENT").ok().as_deref())
+            .unwrap_or_else(|err| panic!("{err}"));
+    pub(crate) static ref TEST_KIND_SAMPLE_SEED: String =
+        env::var("TEST_KIND_SAMPLE_SEED").unwrap_or_default();
+    pub(crate) static ref TEST_KIND_DEFAULT_PRIORITY: i64 =
+        priority_from_env("TEST_KIND_DEFAULT_PRIORITY").unwrap_or(0);
+    pub(crate) static ref TEST_KIND_QUARANTINE: QuarantineMode =
+        read_quarantine(env::var("TEST_KIND_QUARANTINE").ok().as_deref())
+            .unwrap_or_else(|err| panic!("{err}"));
+    pub static ref TEST_KIND_SKIP_MODE: SkipMode =
+        read_skip_mode(env::var("TEST_KIND_SKIP_MODE").ok().as_deref())
+            .unwrap_or_else(|err| panic!("{err}"));
+    pub static ref TEST_KIND_IGNORE_PLAIN: bool = read_env_var_flag("TEST_KIND_IGNORE_PLAIN");
+    pub static ref TEST_KIND_BENCH_HARNESS: BenchHarness =
+        read_bench_harness(env::var("TEST_KIND_BENCH_HARNESS").ok().as_deref())
+            .unwrap_or_else(|err| panic!("{err}"));
+    pub(crate) static ref TEST_KIND_MISSING_RESOURCE_MODE: MissingResourceMode =
+        read_missing_resource_mode(
+            env::var("TEST_KIND_MISSING_RESOURCE_MODE").ok().as_deref(),
+            read_env_var_flag("TEST_KIND_REQUIRE_RESOURCES"),
+        )
+        .unwrap_or_else(|err| panic!("{err}"));
+    pub static ref TEST_KIND_CASE_SENSITIVE: bool = read_env_var_flag("TEST_KIND_CASE_SENSITIVE");
+    static ref TEST_KIND_DEBUG: bool = read_env_var_flag("TEST_KIND_DEBUG");
+    static ref TEST_KIND_LOG: LogMode = read_log_mode(env::var("TEST_KIND_LOG").ok().as_deref())
+        .unwrap_or_else(|err| panic!("{err}"));
+    pub(crate) static ref TEST_KIND_WARN_AGED: bool = read_env_var_flag("TEST_KIND_WARN_AGED");
+    pub(crate) static ref TEST_KIND_WARN_STALE_AUTHORING: bool =
+        read_env_var_flag("TEST_KIND_WARN_STALE_AUTHORING");
+    pub(crate) static ref TEST_KIND_UPDATED_POLICY: UpdatedPolicy =
+        read_updated_policy(env::var("TEST_KIND_UPDATED_POLICY").ok().as_deref())
+            .unwrap_or_else(|err| panic!("{err}"));
+    pub(crate) static ref TEST_KIND_ALLOW_FUTURE_UPDATED: bool =
+        read_env_var_flag("TEST_KIND_ALLOW_FUTURE_UPDATED");
+    pub(crate) static ref TEST_KIND_FORCE_RUN: bool = read_env_var_flag("TEST_KIND_FORCE_RUN");
+    pub(crate) static ref TEST_KIND_DRY_RUN: bool = read_env_var_flag("TEST_KIND_DRY_RUN");
+    pub(crate) static ref TEST_KIND_NO_RETRY: bool = read_env_var_flag("TEST_KIND_NO_RETRY");
+    pub(crate) static ref TEST_KIND_NO_TIMEOUT: bool = read_env_var_flag("TEST_KIND_NO_TIMEOUT");
+    pub(crate) static ref TEST_KIND_RUN_MANUAL: bool = read_env_var_flag("TEST_KIND_RUN_MANUAL");
+    pub(crate) static ref TEST_KIND_EXCLUDE_ALL_OTHER: bool =
+        read_env_var_flag("TEST_KIND_EXCLUDE_ALL_OTHER");
+    pub(crate) static ref TEST_KIND_REPORT: Option<String> = env::var("TEST_KIND_REPORT")
+        .ok()
+        .filter(|value| !value.trim().is_empty());
+    pub(crate) static ref TEST_KIND_SUMMARY: Option<String> = env::var("TEST_KIND_SUMMARY")
+        .ok()
+        .filter(|value| !value.trim().is_empty());
+    pub static ref TEST_KIND_DEFAULT: Option<String> = env::var("TEST_KIND_DEFAULT")
+        .ok()
+        .filter(|value| !value.trim().is_empty());
+    pub(crate) static ref TEST_KIND_PROFILE: Option<String> = env::var("TEST_KIND_PROFILE")
+        .ok()
+        .map(|value| value.trim().to_owned())
+        .filter(|value| !value.is_empty());
+    pub(crate) static ref TEST_KIND_RUN_ID: Option<String> = env::var("TEST_KIND_RUN_ID")
+        .ok()
+        .map(|value| value.trim().to_owned())
+        .filter(|value| !value.is_empty());
+}
+
+/// The current date, in the local timezone.
+///
+/// Every date is validated and aged against this, so they always agree on what today is.
+pub(crate) fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// Read an env var which is a flag, and is set when its value is `1`.
+fn read_env_var_flag(env_var: &str) -> bool {
+    env::var(env_var).is_ok_and(|value| value.trim() == "1")
+}
+
+/// Read an env var which contains a comma separated list of items.
+fn read_env_var_list(env_var: &str) -> Vec<String> {
+    parse_list(&env::var(env_var).unwrap_or_else(|_| String::new()))
+}
+
+/// Parse a comma separated list of items.
+///
+/// Surrounding whitespace is trimmed from the items, such that `foo, foo bar` becomes
+/// `["foo", "foo bar"]`, the same as resources given to the `test_kind` macro.
+/// Anything after a `#` in an item is a comment, so `end2end # slow, api` is `["end2end", "api"]`.
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|s| s.split('#').next().unwrap_or_default().trim().to_owned())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Read the resources listed in the file at `path`, from `TEST_KIND_RESOURCES_FILE`.
+///
+/// Resources are separated by commas or newlines. A file which does not exist, or no file,
+/// has no resources, unless it is `required`.
+///
+/// Returns an error message if the file can not be read, or is required but missing.
+fn read_resources_file(path: Option<&str>, required: bool) -> Result<Vec<String>, String> {
+    let path = match path.map(str::trim) {
+        Some(path) if !path.is_empty() => path,
+        _ if required => {
+            return Err(
+                "TEST_KIND_RESOURCES_FILE_REQUIRED=1, but TEST_KIND_RESOURCES_FILE is not set"
+                    .to_owned(),
+            )
+        }
+        _ => return Ok(Vec::new()),
+    };
+
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(contents.lines().flat_map(parse_list).collect()),
+        Err(err) if err.kind() == ErrorKind::NotFound && !required => Ok(Vec::new()),
+        Err(err) => Err(format!(
+            "TEST_KIND_RESOURCES_FILE={path} can not be read: {err}"
+        )),
+    }
+}
+
+/// The earliest `updated` date allowed when `TEST_KIND_MIN_UPDATED` is not set.
+const DEFAULT_MIN_UPDATED: (i32, u32, u32) = (2023, 10, 10);
+
+/// Read the earliest `updated` date allowed, from `TEST_KIND_MIN_UPDATED` in the format
+/// YYYY-MM-DD, or the default when it is unset or empty.
+///
+/// Returns an error message if it is set to something which is not a valid date.
+fn read_min_updated(value: Option<&str>) -> Result<NaiveDate, String> {
+    match value.map(str::trim) {
+        None | Some("") => {
+            let (year, month, day) = DEFAULT_MIN_UPDATED;
+            #[allow(clippy::unwrap_used)]
+            Ok(NaiveDate::from_ymd_opt(year, month, day).unwrap()) // Can't panic
+        }
+        Some(value) => NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|err| {
+            format!("TEST_KIND_MIN_UPDATED={value} is invalid, it must be YYYY-MM-DD: {err}")
+        }),
+    }
+}
+
+/// Which `updated` dates are plausible, beyond being valid and in range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdatedPolicy {
+    /// Any date is allowed.
+    Any,
+    /// Dates on a Saturday or Sunday are rejected.
+    BusinessDays,
+}
+
+impl UpdatedPolicy {
+    /// Check the `date` given to `key` is allowed by the policy.
+    ///
+    /// Returns an error message if it is not.
+    pub(crate) fn check(self, key: &str, date: NaiveDate) -> Result<(), String> {
+        match self {
+            UpdatedPolicy::Any => Ok(()),
+            UpdatedPolicy::BusinessDays => match date.weekday() {
+                Weekday::Sat | Weekday::Sun => Err(format!(
+                    "`{key}={date}` is a {}, but `TEST_KIND_UPDATED_POLICY=business_days` \
+                     only allows dates from Monday to Friday",
+                    date.format("%A")
+                )),
+                _ => Ok(()),
+            },
+        }
+    }
+}
+
+/// Read which `updated` dates are plausible, from `TEST_KIND_UPDATED_POLICY`,
+/// which is `any` or `business_days`. It is `any` when unset or empty.
+///
+/// Returns an error message if it is set to anything else.
+fn read_updated_policy(value: Option<&str>) -> Result<UpdatedPolicy, String> {
+    match value.map(str::trim) {
+        None | Some("") => Ok(UpdatedPolicy::Any),
+        Some(value) if value.eq_ignore_ascii_case("any") => Ok(UpdatedPolicy::Any),
+        Some(value) if value.eq_ignore_ascii_case("business_days") => {
+            Ok(UpdatedPolicy::BusinessDays)
+        }
+        Some(value) => Err(format!(
+            "TEST_KIND_UPDATED_POLICY={value} is invalid, it must be `any` or `business_days`"
+        )),
+    }
+}
+
+/// Parse the priority in an env var.
+///
+/// Returns `None` if the env var is not set or is empty, and an error message if it is
+/// set to something which is not a whole number.
+fn read_priority(env_var: &str, value: Option<&str>) -> Result<Option<i64>, String> {
+    match value.map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| format!("{env_var}={value} is invalid, it must be a whole number")),
+    }
+}
+
+/// Parse the percentage of tests to sample, from `TEST_KIND_SAMPLE_PERCENT`.
+///
+/// Returns `None` if it is not set or is empty, and an error message if it is
+/// set to something which is not a whole number from 0 to 100.
+fn read_sample_percent(value: Option<&str>) -> Result<Option<u32>, String> {
+    match value.map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(value) => value
+            .parse()
+            .ok()
+            .filter(|percent| *percent <= 100)
+            .map(Some)
+            .ok_or_else(|| {
+                format!(
+                    "TEST_KIND_SAMPLE_PERCENT={value} is invalid, it must be a whole number from 0 to 100"
+                )
+            }),
+    }
+}
+
+/// Read the priority in an env var, if it is set.
+///
+/// Panics if the env var is set, but is not a whole number, so a misconfiguration
+/// fails the build rather than being silently ignored.
+fn priority_from_env(env_var: &str) -> Option<i64> {
+    let value = env::var(env_var).ok();
+    read_priority(env_var, value.as_deref()).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Read the counts of each kind, from the env vars named with `prefix` and then the kind,
+/// such that `TEST_KIND_MIN_RESOURCES_end2end=2` becomes `("end2end", 2)`.
+///
+/// Returns an error message if any of them are not a whole number.
+fn read_kind_counts(
+    vars: impl Iterator<Item = (String, String)>,
+    prefix: &str,
+) -> Result<Vec<(String, usize)>, String> {
+    vars.filter_map(|(env_var, value)| {
+        let kind = env_var.strip_prefix(prefix)?;
+        Some(
+            value
+                .trim()
+                .parse()
+                .map(|count| (kind.to_owned(), count))
+                .map_err(|_| format!("{env_var}={value} is invalid, it must be a whole number")),
+        )
+    })
+    .collect()
+}
+
+/// What to do when a quarantined test fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum QuarantineMode {
+    /// Catch the failure and report it, without failing the test.
+    Report,
+    /// Fail the test, like any other test.
+    Enforce,
+}
+
+/// Read what to do when a quarantined test fails, from `TEST_KIND_QUARANTINE`, which is
+/// `report` or `enforce`. It is `report` when unset or empty.
+///
+/// Returns an error message if it is set to anything else.
+fn read_quarantine(value: Option<&str>) -> Result<QuarantineMode, String> {
+    match value.map(str::trim) {
+        None | Some("") => Ok(QuarantineMode::Report),
+        Some(value) if value.eq_ignore_ascii_case("report") => Ok(QuarantineMode::Report),
+        Some(value) if value.eq_ignore_ascii_case("enforce") => Ok(QuarantineMode::Enforce),
+        Some(value) => Err(format!(
+            "TEST_KIND_QUARANTINE={value} is invalid, it must be `report` or `enforce`"
+        )),
+    }
+}
+
+/// What to do with tests which are skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipMode {
+    /// Keep the test, but ignore it with the reason it was skipped.
+    Ignore,
+    /// Drop the test, like tests which are ignored silently.
+    Drop,
+}
+
+/// Read what to do with skipped tests, from `TEST_KIND_SKIP_MODE`, which is `ignore` or `drop`.
+/// It is `ignore` when unset or empty.
+///
+/// Returns an error message if it is set to anything else.
+fn read_skip_mode(value: Option<&str>) -> Result<SkipMode, String> {
+    match value.map(str::trim) {
+        None | Some("") => Ok(SkipMode::Ignore),
+        Some(value) if value.eq_ignore_ascii_case("ignore") => Ok(SkipMode::Ignore),
+        Some(value) if value.eq_ignore_ascii_case("drop") => Ok(SkipMode::Drop),
+        Some(value) => Err(format!(
+            "TEST_KIND_SKIP_MODE={value} is invalid, it must be `ignore` or `drop`"
+        )),
+    }
+}
+
+/// The harness which runs `bench` tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BenchHarness {
+    /// The nightly only `#[bench]` attribute.
+    Bench,
+    /// Plain functions, which are run by criterion's `criterion_group!`.
+    Criterion,
+}
+
+/// Read the harness of `bench` tests, from `TEST_KIND_BENCH_HARNESS`, which is `bench` or
+/// `criterion`. It is `bench` when unset or empty.
+///
+/// Returns an error message if it is set to anything else.
+fn read_bench_harness(value: Option<&str>) -> Result<BenchHarness, String> {
+    match value.map(str::trim) {
+        None | Some("") => Ok(BenchHarness::Bench),
+        Some(value) if value.eq_ignore_ascii_case("bench") => Ok(BenchHarness::Bench),
+        Some(value) if value.eq_ignore_ascii_case("criterion") => Ok(BenchHarness::Criterion),
+        Some(value) => Err(format!(
+            "TEST_KIND_BENCH_HARNESS={value} is invalid, it must be `bench` or `criterion`"
+        )),
+    }
+}
+
+/// How diagnostics are logged as tests are compiled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LogMode {
+    /// Nothing is logged.
+    Off,
+    /// One JSON object per line.
+    Json,
+    /// One line of text per event, starting with the kind so they can be grouped by it.
+    Pretty,
+}
+
+/// Read how diagnostics are logged, from `TEST_KIND_LOG`, which is `off`, `json` or `pretty`.
+/// It is `off` when unset or empty.
+///
+/// Returns an error message if it is set to anything else.
+fn read_log_mode(value: Option<&str>) -> Result<LogMode, String> {
+    match value.map(str::trim) {
+        None | Some("") => Ok(LogMode::Off),
+        Some(value) if value.eq_ignore_ascii_case("off") => Ok(LogMode::Off),
+        Some(value) if value.eq_ignore_ascii_case("json") => Ok(LogMode::Json),
+        Some(value) if value.eq_ignore_ascii_case("pretty") => Ok(LogMode::Pretty),
+        Some(value) => Err(format!(
+            "TEST_KIND_LOG={value} is invalid, it must be `off`, `json` or `pretty`"
+        )),
+    }
+}
+
+/// What to do with tests of other kinds when a resource they require is missing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MissingResourceMode {
+    /// Skip the test, with the reason saying which resources are missing.
+    Skip,
+    /// Ignore the test silently, so it is not reported as skipped.
+    Drop,
+    /// Fail to compile the test, as the resource should have been available.
+    Fail,
+}
+
+/// Read what to do with tests which are missing resources, from
+/// `TEST_KIND_MISSING_RESOURCE_MODE`, which is `skip`, `drop` or `fail`.
+/// It is `skip` when unset or empty, and always `fail` when resources are `required`,
+/// by `TEST_KIND_REQUIRE_RESOURCES=1`.
+///
+/// Returns an error message if it is set to anything else.
+fn read_missing_resource_mode(
+    value: Option<&str>,
+    required: bool,
+) -> Result<MissingResourceMode, String> {
+    let mode = match value.map(str::trim) {
+        None | Some("") => MissingResourceMode::Skip,
+        Some(value) if value.eq_ignore_ascii_case("skip") => MissingResourceMode::Skip,
+        Some(value) if value.eq_ignore_ascii_case("drop") => MissingResourceMode::Drop,
+        Some(value) if value.eq_ignore_ascii_case("fail") => MissingResourceMode::Fail,
+        Some(value) => {
+            return Err(format!(
+                "TEST_KIND_MISSING_RESOURCE_MODE={value} is invalid, it must be `skip`, `drop` or `fail`"
+            ))
+        }
+    };
+    Ok(if required {
+        MissingResourceMode::Fail
+    } else {
+        mode
+    })
+}
+
+/// Parse resource groups, such that `ci-full=db;redis` becomes `("ci-full", ["db", "redis"])`.
+///
+/// Items which are not of the form `group=resource;resource` are ignored.
+fn read_resource_groups(items: &[String]) -> Vec<(String, Vec<String>)> {
+    items
+        .iter()
+        .filter_map(|item| {
+            let (group, resources) = item.split_once('=')?;
+            let group = group.trim();
+            let resources: Vec<String> = resources
+                .split(';')
+                .map(str::trim)
+                .filter(|resource| !resource.is_empty())
+                .map(ToOwned::to_owned)
+                .collect();
+            (!group.is_empty()).then(|| (group.to_owned(), resources))
+        })
+        .collect()
+}
+
+/// Prefix of an available resource which removes it, such as `-gpu`.
+const REMOVED_PREFIX: char = '-';
+
+/// Expand the available resources with the resources of every group they name.
+///
+/// Groups expand transitively, and a group which contains itself, directly or
+/// through other groups, is only expanded once.
+/// Resources prefixed with `REMOVED_PREFIX` are then removed, with any version, along with
+/// the resources of the groups they name, wherever they are in the list.
+/// When every resource is available, by `ALL_RESOURCES`, the only other resources are those
+/// which are removed, still with their prefix, as every resource but them is available.
+fn expand_resources(resources: &[String], groups: &[(String, Vec<String>)]) -> HashSet<String> {
+    let (removed, added): (Vec<String>, Vec<String>) = resources
+        .iter()
+        .cloned()
+        .partition(|resource| resource.starts_with(REMOVED_PREFIX));
+    let removed: Vec<String> = removed
+        .iter()
+        .map(|resource| resource[REMOVED_PREFIX.len_utf8()..].trim().to_owned())
+        .collect();
+    let removed = expand_group_members(&removed, groups);
+
+    if added.iter().any(|resource| resource == ALL_RESOURCES) {
+        let mut available: HashSet<String> = removed
+            .iter()
+            .map(|resource| format!("{REMOVED_PREFIX}{resource}"))
+            .collect();
+        available.insert(ALL_RESOURCES.to_owned());
+        return available;
+    }
+
+    let mut available = expand_group_members(&added, groups);
+    available.retain(|resource| {
+        let name = resource
+            .split_once('=')
+            .map_or(resource.as_str(), |(name, _)| name.trim());
+        !removed.iter().any(|removed| names_match(removed, name))
+    });
+    available
+}
+
+/// The `resources`, and the resources of every group they name, expanded transitively.
+fn expand_group_members(resources: &[String], groups: &[(String, Vec<String>)]) -> HashSet<String> {
+    let mut available = HashSet::new();
+    let mut pending: Vec<String> = resources.to_vec();
+
+    while let Some(resource) = pending.pop() {
+        if available.contains(&resource) {
+            continue;
+        }
+        for (group, members) in groups {
+            if names_match(group, &resource) {
+                pending.extend(members.iter().cloned());
+            }
+        }
+        available.insert(resource);
+    }
+
+    available
+}
+
+/// The resources in a `group`, with any groups in it expanded, in the order they are listed.
+///
+/// Returns `None` if it is not one of the `groups`.
+pub(crate) fn group_members(group: &str, groups: &[(String, Vec<String>)]) -> Option<Vec<String>> {
+    let members_of = |name: &str| -> Option<Vec<String>> {
+        let members: Vec<String> = groups
+            .iter()
+            .filter(|(group, _)| names_match(group, name))
+            .flat_map(|(_, members)| members.iter().cloned())
+            .collect();
+        groups
+            .iter()
+            .any(|(group, _)| names_match(group, name))
+            .then_some(members)
+    };
+
+    let mut expanded_groups = vec![group.to_owned()];
+    let mut pending: Vec<String> = members_of(group)?;
+    pending.reverse();
+    let mut members: Vec<String> = Vec::new();
+    while let Some(name) = pending.pop() {
+        if let Some(mut group_members) = members_of(&name) {
+            // A group which contains itself, directly or through other groups, is expanded once.
+            if !expanded_groups
+                .iter()
+                .any(|group| names_match(group, &name))
+            {
+                group_members.reverse();
+                pending.extend(group_members);
+                expanded_groups.push(name);
+            }
+        } else if !members.iter().any(|member| names_match(member, &name)) {
+            members.push(name);
+        }
+    }
+    Some(members)
+}
+
+/// The resource groups, from `TEST_KIND_RESOURCE_GROUPS`.
+pub(crate) fn resource_groups() -> &'static [(String, Vec<String>)] {
+    &TEST_KIND_RESOURCE_GROUPS
+}
+
+/// The available resources, with every group in `TEST_KIND_RESOURCE_GROUPS` expanded.
+pub(crate) fn expanded_available_resources() -> &'static HashSet<String> {
+    &TEST_KIND_AVAILABLE_RESOURCES
+}
+
+/// Read the available resources from `TEST_KIND_RESOURCES`, and `TEST_KIND_RESOURCES_FILE`
+/// if it is set.
+///
+/// Panics if the resources file can not be read, or a resource name is invalid.
+fn read_available_resources() -> Vec<String> {
+    let mut resources = read_env_var_list("TEST_KIND_RESOURCES");
+    resources.extend(
+        read_resources_file(
+            env::var("TEST_KIND_RESOURCES_FILE").ok().as_deref(),
+            read_env_var_flag("TEST_KIND_RESOURCES_FILE_REQUIRED"),
+        )
+        .unwrap_or_else(|err| panic!("{err}")),
+    );
+    check_available_resources(&resources).unwrap_or_else(|err| panic!("{err}"));
+    resources
+}
+
+/// Check the name of each available resource, ignoring `ALL_RESOURCES`, the `REMOVED_PREFIX`
+/// of removed resources and any version, such as `postgres=16`.
+///
+/// Returns an error message for the first invalid name, as a resource with it could never be
+/// required, such as `my service`.
+fn check_available_resources(resources: &[String]) -> Result<(), String> {
+    resources
+        .iter()
+        .filter(|resource| *resource != ALL_RESOURCES)
+        .try_for_each(|resource| {
+            let name = resource.strip_prefix(REMOVED_PREFIX).unwrap_or(resource);
+            check_available_name(name)
+                .map_err(|err| format!("{err}, in TEST_KIND_RESOURCES or TEST_KIND_RESOURCES_FILE"))
+        })
+}
+
+/// Read the available resources as they are now, rather than when they were first read,
+/// with every group in `TEST_KIND_RESOURCE_GROUPS` expanded.
+///
+/// Panics if the resources file can not be read, or a resource name is invalid.
+pub(crate) fn current_available_resources() -> HashSet<String> {
+    expand_resources(
+        &read_available_resources(),
+        &read_resource_groups(&read_env_var_list("TEST_KIND_RESOURCE_GROUPS")),
+    )
+}
+
+/// The attributes given to a macro, or the `default` attributes when it was given none.
+///
+/// Returns `None` when it was given none, and there is no default.
+pub fn attributes_or_default(attributes: &str, default: Option<&str>) -> Option<String> {
+    if attributes.trim().is_empty() {
+        default.map(ToOwned::to_owned)
+    } else {
+        Some(attributes.to_owned())
+    }
+}
+
+/// Print a diagnostic message, but only when `TEST_KIND_DEBUG=1`.
+pub fn debug(message: &str) {
+    if *TEST_KIND_DEBUG {
+        eprintln!("{message}");
+    }
+}
+
+/// Format the log line of an `event` and its `fields`, or `None` if nothing is logged.
+///
+/// Fields without a value are `null` in JSON, and left out of pretty lines.
+fn log_line(
+    mode: LogMode,
+    event: &str,
+    kind: &str,
+    fields: &[(&str, Option<&str>)],
+) -> Option<String> {
+    match mode {
+        LogMode::Off => None,
+        LogMode::Json => {
+            let fields: Vec<String> = [("event", Some(event)), ("kind", Some(kind))]
+                .iter()
+                .chain(fields)
+                .map(|(key, value)| {
+                    let value = value.map_or_else(|| "null".to_owned(), json_string);
+                    format!("{}:{value}", json_string(key))
+                })
+                .collect();
+            Some(format!("{{{}}}", fields.join(",")))
+        }
+        LogMode::Pretty => {
+            let fields: Vec<String> = fields
+                .iter()
+                .filter_map(|(key, value)| value.map(|value| format!("{key}={value:?}")))
+                .collect();
+            Some(format!("[{kind}] {event}: {}", fields.join(" ")))
+        }
+    }
+}
+
+/// Log an `event` about a `kind` of test to stderr, according to `TEST_KIND_LOG`.
+///
+/// Tests are compiled in parallel, so each event is written as a single line.
+fn log(event: &str, kind: &str, fields: &[(&str, Option<&str>)]) {
+    if let Some(line) = log_line(*TEST_KIND_LOG, event, kind, fields) {
+        eprintln!("{line}");
+    }
+}
+
+/// Log what was decided to do with a test, according to `TEST_KIND_LOG`.
+pub fn log_decision(name: &str, kind: &str, settings: &TestSettings) {
+    let reason = settings.reason();
+    log(
+        "decision",
+        kind,
+        &[
+            ("name", Some(name)),
+            ("decision", Some(settings.decision())),
+            ("reason", reason.as_deref()),
+        ],
+    );
+}
+
+/// Do two names match?
+///
+/// Names are matched exactly when `case_sensitive`, otherwise ASCII case is ignored.
+pub(crate) fn names_match_with(case_sensitive: bool, name: &str, other: &str) -> bool {
+    if case_sensitive {
+        name == other
+    } else {
+        name.eq_ignore_ascii_case(other)
+    }
+}
+
+/// Do two names match, according to `TEST_KIND_CASE_SENSITIVE`?
+pub(crate) fn names_match(name: &str, other: &str) -> bool {
+    names_match_with(*TEST_KIND_CASE_SENSITIVE, name, other)
+}
+
+/// Does a name match a pattern, where `*` in the pattern matches any characters?
+///
+/// A pattern without a `*` is matched like any other name, see `names_match_with`.
+fn pattern_matches_with(case_sensitive: bool, pattern: &str, name: &str) -> bool {
+    let (pattern, name) = if case_sensitive {
+        (pattern.to_owned(), name.to_owned())
+    } else {
+        (pattern.to_ascii_lowercase(), name.to_ascii_lowercase())
+    };
+
+    let mut parts = pattern.split('*');
+    // There is always a first part, even if it is empty.
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // No `*`, so the whole name must match.
+        return rest.is_empty();
+    };
+
+    // Each middle part matches at its earliest position, leaving the most for the rest.
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Does a name match a pattern, according to `TEST_KIND_CASE_SENSITIVE`?
+fn pattern_matches(pattern: &str, name: &str) -> bool {
+    pattern_matches_with(*TEST_KIND_CASE_SENSITIVE, pattern, name)
+}
+
+/// The first of the `patterns` which matches the name, if any do.
+fn matching_pattern<'a>(
+    case_sensitive: bool,
+    patterns: &'a [String],
+    name: &str,
+) -> Option<&'a str> {
+    patterns
+        .iter()
+        .find(|pattern| pattern_matches_with(case_sensitive, pattern, name))
+        .map(String::as_str)
+}
+
+/// An excluded kind which excludes every kind of test.
+const ALL_KINDS: &str = "all";
+
+/// The entry of the `excluded` kinds which excludes the test `kind`, if it is excluded.
+///
+/// `ALL_KINDS` excludes every kind, whatever the other entries are. It is matched like a
+/// kind, so when `case_sensitive` only `all` is, and `ALL` is an ordinary kind.
+fn excluded_kind_by<'a>(
+    case_sensitive: bool,
+    excluded: &'a [String],
+    kind: &str,
+) -> Option<&'a str> {
+    excluded
+        .iter()
+        .find(|pattern| names_match_with(case_sensitive, pattern, ALL_KINDS))
+        .map(String::as_str)
+        .or_else(|| matching_pattern(case_sensitive, excluded, kind))
+}
+
+/// The entry of `TEST_KIND_EXCLUDE` which excludes a test kind, if it is excluded.
+///
+/// Excluded kinds may contain `*` wildcards, so `api-*` excludes `api-v1` and `api-v2`,
+/// and `all` excludes every kind.
+pub(crate) fn test_kind_excluded_by(kind: &str) -> Option<&'static str> {
+    let excluded_by = excluded_kind_by(*TEST_KIND_CASE_SENSITIVE, &TEST_KIND_EXCLUDE, kind);
+    let excluded = excluded_by.is_some();
+    debug(&format!(
+        "Check test of kind: {kind} are excluded: {excluded}"
+    ));
+    log(
+        "exclude",
+        kind,
+        &[("excluded", Some(if excluded { "true" } else { "false" }))],
+    );
+    excluded_by
+}
+
+/// Check if a test resource is excluded or not.
+///
+/// Excluded resources may contain `*` wildcards, like excluded kinds.
+pub(crate) fn is_test_resource_excluded(resource: &str) -> bool {
+    TEST_KIND_EXCLUDE_RESOURCE
+        .iter()
+        .any(|s| pattern_matches(s, resource))
+}
+
+/// Check if a test kind is included or not.
+pub(crate) fn is_test_kind_included(kind: &str) -> bool {
+    // If the env var is not defined, everything is included.
+    if TEST_KIND_INCLUDE.is_empty() {
+        return true;
+    }
+    // Otherwise only the listed kinds of tests are included.
+    TEST_KIND_INCLUDE.iter().any(|s| names_match(s, kind))
+}
+
+/// Get the age settings of an other kind of test, if it has any.
+pub(crate) fn other_kind_age(kind: &str) -> Option<&'static UnitAge> {
+    TEST_KIND_OTHER_AGES
+        .iter()
+        .find(|(other_kind, _)| names_match(other_kind, kind))
+        .map(|(_, age)| age)
+}
+
+/// An available resource which makes every resource available, such as on a fully
+/// provisioned runner.
+const ALL_RESOURCES: &str = "*";
+
+/// Are all resources available, by `ALL_RESOURCES` in the `available` resources?
+pub(crate) fn all_resources_available(available: &HashSet<String>) -> bool {
+    available.contains(ALL_RESOURCES)
+}
+
+/// Is the resource `name` removed, by being prefixed with `REMOVED_PREFIX` in the `available`
+/// resources, when all resources are available?
+fn is_removed(available: &HashSet<String>, name: &str) -> bool {
+    available
+        .iter()
+        .filter_map(|resource| resource.strip_prefix(REMOVED_PREFIX))
+        .any(|removed| names_match(removed, name))
+}
+
+/// Is the `resource` requirement satisfied by the `available` resources?
+///
+/// When all resources are available, every resource but those which are removed is, whatever
+/// its version. So a required resource is satisfied unless it is removed, and a forbidden
+/// resource only when it is removed.
+pub(crate) fn is_requirement_satisfied(
+    resource: &ResourceReq,
+    available: &HashSet<String>,
+) -> bool {
+    if !all_resources_available(available) {
+        return resource.is_satisfied(available);
+    }
+    let any_available = resource
+        .names()
+        .into_iter()
+        .any(|name| !is_removed(available, name));
+    any_available != resource.is_forbidden()
+}
+
+/// The `resources` which are not satisfied by the `available` resources.
+///
+/// They are kept in the order they were declared, so skip reasons are the same on every run.
+fn unsatisfied_resources(
+    resources: &[ResourceReq],
+    available: &HashSet<String>,
+) -> Vec<ResourceReq> {
+    resources
+        .iter()
+        .filter(|resource| !is_requirement_satisfied(resource, available))
+        .cloned()
+        .collect()
+}
+
+/// Check if a list of resources is found in the available resources.
+/// Returns a list of the resources which are not satisfied, in the order they were declared.
+pub(crate) fn has_resources_available(resources: &[ResourceReq]) -> Vec<ResourceReq> {
+    unsatisfied_resources(resources, expanded_available_resources())
+}
+
+/// Kinds of tests which are built in, and so are always defined.
+const BUILT_IN_KINDS: &[&str] = &["unit", "integration", "bench", "manual"];
+
+/// Is the test `kind` one of the `defined` kinds?
+///
+/// Every kind is defined when none are, and built in kinds are always defined.
+fn kind_is_defined(defined: &[String], case_sensitive: bool, kind: &str) -> bool {
+    defined.is_empty()
+        || BUILT_IN_KINDS.contains(&kind)
+        || defined
+            .iter()
+            .any(|defined| names_match_with(case_sensitive, defined, kind))
+}
+
+/// Check if a test kind is defined or not, by `TEST_KIND_DEFINED`.
+pub(crate) fn is_test_kind_defined(kind: &str) -> bool {
+    kind_is_defined(&TEST_KIND_DEFINED, *TEST_KIND_CASE_SENSITIVE, kind)
+}
+
+/// Get the minimum and maximum number of resources a test of the kind can require,
+/// from `TEST_KIND_MIN_RESOURCES_<kind>` and `TEST_KIND_MAX_RESOURCES_<kind>`.
+pub(crate) fn resource_count_limits(kind: &str) -> (Option<usize>, Option<usize>) {
+    let limit = |counts: &[(String, usize)]| {
+        counts
+            .iter()
+            .find(|(counted_kind, _)| names_match(counted_kind, kind))
+            .map(|(_, count)| *count)
+    };
+    (
+        limit(&TEST_KIND_MIN_RESOURCES),
+        limit(&TEST_KIND_MAX_RESOURCES),
+    )
+}
+
+/// Check if the known test resources are required, but not set.
+///
+/// Once the test kinds are defined, the resources they use must be defined too.
+pub(crate) fn are_known_resources_missing() -> bool {
+    !TEST_KIND_DEFINED.is_empty() && TEST_KIND_KNOWN_RESOURCES.is_empty()
+}
+
+/// Check if a test resource is listed in `TEST_KIND_KNOWN_RESOURCES`.
+///
+/// Unlike `is_test_resource_defined`, nothing is listed when the env var is not defined.
+pub(crate) fn is_test_resource_listed(resource: &str) -> bool {
+    TEST_KIND_KNOWN_RESOURCES
+        .iter()
+        .any(|s| names_match(s, resource))
+}
+
+/// Check if a test resource defined or not.
+pub(crate) fn is_test_resource_defined(resource: &str) -> bool {
+    // If the env var is not defined, everything is defined.
+    if TEST_KIND_KNOWN_RESOURCES.is_empty() {
+        return true;
+    }
+    // Otherwise only the listed kinds of test resources are defined.
+    TEST_KIND_KNOWN_RESOURCES
+        .iter()
+        .any(|s| names_match(s, resource))
+}
+
+/// The configuration read from the `TEST_KIND_*` env vars.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Kinds of tests which are excluded, from `TEST_KIND_EXCLUDE`.
+    pub exclude: Vec<String>,
+    /// Only kinds of tests which are included, from `TEST_KIND_INCLUDE`, all if empty.
+    pub include: Vec<String>,
+    /// Maximum number of days a unit test runs for in CI, from `TEST_KIND_UNIT_AGE`.
+    pub unit_age: u32,
+    /// Number of days an aged out unit test is skipped, from `TEST_KIND_UNIT_SKIP`.
+    pub unit_skip: u32,
+    /// Resources whose tests are excluded, from `TEST_KIND_EXCLUDE_RESOURCE`.
+    pub exclude_resources: Vec<String>,
+    /// Kinds of tests which are defined, from `TEST_KIND_DEFINED`, all if empty.
+    pub defined: Vec<String>,
+    /// Resources which are known, from `TEST_KIND_KNOWN_RESOURCES`, all if empty.
+    pub known_resources: Vec<String>,
+    /// Resources which are available, from `TEST_KIND_RESOURCES` with every group expanded.
+    /// With `*`, they are `*` and the resources removed from it, such as `-gpu`.
+    pub available_resources: Vec<String>,
+    /// Profile whose resources tests require, from `TEST_KIND_PROFILE`.
+    pub profile: Option<String>,
+}
+
+impl Config {
+    /// Read the configuration from the `TEST_KIND_*` env vars.
+    pub(crate) fn from_env() -> Self {
+        let mut available_resources: Vec<String> =
+            expanded_available_resources().iter().cloned().collect();
+        available_resources.sort();
+
+        Self {
+            exclude: TEST_KIND_EXCLUDE.clone(),
+            include: TEST_KIND_INCLUDE.clone(),
+            unit_age: TEST_KIND_UNIT_AGE.max(),
+            unit_skip: TEST_KIND_UNIT_AGE.skip(),
+            exclude_resources: TEST_KIND_EXCLUDE_RESOURCE.clone(),
+            defined: TEST_KIND_DEFINED.clone(),
+            known_resources: TEST_KIND_KNOWN_RESOURCES.clone(),
+            available_resources,
+            profile: TEST_KIND_PROFILE.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |items: &[String], empty: &'static str| {
+            if items.is_empty() {
+                empty.to_owned()
+            } else {
+                items.join(", ")
+            }
+        };
+
+        writeln!(f, "TEST_KIND_EXCLUDE: {}", list(&self.exclude, "(none)"))?;
+        writeln!(f, "TEST_KIND_INCLUDE: {}", list(&self.include, "(all)"))?;
+        writeln!(f, "TEST_KIND_UNIT_AGE: {} days", self.unit_age)?;
+        writeln!(f, "TEST_KIND_UNIT_SKIP: {} days", self.unit_skip)?;
+        writeln!(
+            f,
+            "TEST_KIND_EXCLUDE_RESOURCE: {}",
+            list(&self.exclude_resources, "(none)")
+        )?;
+        writeln!(f, "TEST_KIND_DEFINED: {}", list(&self.defined, "(all)"))?;
+        writeln!(
+            f,
+            "TEST_KIND_KNOWN_RESOURCES: {}",
+            list(&self.known_resources, "(all)")
+        )?;
+        writeln!(
+            f,
+            "TEST_KIND_PROFILE: {}",
+            self.profile.as_deref().unwrap_or("(none)")
+        )?;
+        write!(
+            f,
+            "Available resources: {}",
+            list(&self.available_resources, "(none)")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        attributes_or_default, check_available_resources, excluded_kind_by, expand_resources,
+        group_members, kind_is_defined, log_line, matching_pattern, names_match_with, parse_list,
+        pattern_matches_with, read_bench_harness, read_kind_counts, read_log_mode,
+        read_min_updated, read_missing_resource_mode, read_priority, read_quarantine,
+        read_resource_groups, read_resources_file, read_sample_percent, read_skip_mode,
+        read_updated_policy, unsatisfied_resources, BenchHarness, Config, LogMode,
+        MissingResourceMode, QuarantineMode, SkipMode, UpdatedPolicy,
+    };
+    use crate::resource::ResourceReq;
+    use chrono::NaiveDate;
+    use std::collections::HashSet;
+
+    fn list(items: &[&str]) -> Vec<String> {
+        items.iter().map(ToString::to_string).collect()
+    }
+
+    fn set(items: &[&str]) -> HashSet<String> {
+        items.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn names_match_ignoring_case_by_default() {
+        assert!(names_match_with(false, "end2end", "end2end"));
+        assert!(names_match_with(false, "end2end", "End2End"));
+        assert!(!names_match_with(false, "end2end", "e2e"));
+    }
+
+    #[test]
+    fn names_match_exactly_when_case_sensitive() {
+        assert!(names_match_with(true, "end2end", "end2end"));
+        assert!(!names_match_with(true, "end2end", "End2End"));
+        assert!(!names_match_with(true, "end2end", "e2e"));
+    }
+
+    #[test]
+    fn patterns_without_wildcards_match_names() {
+        assert!(pattern_matches_with(false, "api", "API"));
+        assert!(!pattern_matches_with(false, "api", "api-v1"));
+        assert!(!pattern_matches_with(true, "api", "API"));
+    }
+
+    #[test]
+    fn patterns_with_prefix_wildcards_match() {
+        assert!(pattern_matches_with(false, "*-internal", "api-internal"));
+        assert!(pattern_matches_with(false, "*-internal", "-internal"));
+        assert!(!pattern_matches_with(
+            false,
+            "*-internal",
+            "api-internal-v2"
+        ));
+        assert!(pattern_matches_with(false, "*", "anything"));
+    }
+
+    #[test]
+    fn patterns_with_suffix_wildcards_match() {
+        assert!(pattern_matches_with(false, "api-*", "api-v1"));
+        assert!(pattern_matches_with(false, "api-*", "API-Internal"));
+        assert!(pattern_matches_with(false, "api-*", "api-"));
+        assert!(!pattern_matches_with(false, "api-*", "api"));
+        assert!(!pattern_matches_with(true, "api-*", "API-v1"));
+    }
+
+    #[test]
+    fn patterns_with_middle_wildcards_match() {
+        assert!(pattern_matches_with(false, "api-*-slow", "api-v1-slow"));
+        assert!(pattern_matches_with(false, "api*v*", "api-v1-v2"));
+        assert!(pattern_matches_with(false, "a*b*a", "aba"));
+        assert!(!pattern_matches_with(false, "a*a", "a"));
+        assert!(!pattern_matches_with(false, "api-*-slow", "api-v1-fast"));
+    }
+
+    #[test]
+    fn list_items_can_have_comments() {
+        assert_eq!(
+            parse_list("end2end # slow, api,db#needs docker , # nothing"),
+            list(&["end2end", "api", "db"])
+        );
+        assert_eq!(parse_list("end2end, api"), list(&["end2end", "api"]));
+        assert!(parse_list("# only a comment").is_empty());
+    }
+
+    #[test]
+    fn list_items_keep_their_inner_spaces() {
+        assert_eq!(
+            parse_list(" my service , db,, "),
+            list(&["my service", "db"])
+        );
+        assert!(parse_list("").is_empty());
+        // Resource names can not contain spaces, so an available resource with one is an error.
+        assert_eq!(
+            check_available_resources(&parse_list("my service, db")),
+            Err(
+                "Invalid resource name `my service`, it may only contain letters, numbers, `_` \
+                 and `-`, in TEST_KIND_RESOURCES or TEST_KIND_RESOURCES_FILE"
+                    .to_owned()
+            )
+        );
+        assert_eq!(
+            check_available_resources(&parse_list("*, -gpu, postgres = 16, mock_server-2")),
+            Ok(())
+        );
+        assert_eq!(
+            read_resource_groups(&parse_list("ci full = my service; db")),
+            vec![("ci full".into(), list(&["my service", "db"]))]
+        );
+    }
+
+    #[test]
+    fn resource_groups_are_parsed() {
+        let groups = read_resource_groups(&list(&["ci-full=db;redis;kafka", "bad", "=db"]));
+        assert_eq!(
+            groups,
+            vec![("ci-full".into(), list(&["db", "redis", "kafka"]))]
+        );
+    }
+
+    #[test]
+    fn resource_groups_expand_transitively() {
+        let groups = read_resource_groups(&list(&["ci-full=db;queues", "queues=redis;kafka"]));
+        assert_eq!(
+            expand_resources(&list(&["ci-full", "s3"]), &groups),
+            set(&["ci-full", "db", "queues", "redis", "kafka", "s3"])
+        );
+        assert_eq!(
+            expand_resources(&list(&["queues"]), &groups),
+            set(&["queues", "redis", "kafka"])
+        );
+    }
+
+    #[test]
+    fn removed_resources_are_not_available() {
+        let groups = read_resource_groups(&list(&["ci-full=db;gpu", "queues=redis;kafka"]));
+        assert_eq!(
+            expand_resources(&list(&["ci-full", "-gpu"]), &groups),
+            set(&["ci-full", "db"])
+        );
+        // Removals apply wherever they are, with any version.
+        assert_eq!(
+            expand_resources(&list(&["-gpu", "ci-full", "gpu=2"]), &groups),
+            set(&["ci-full", "db"])
+        );
+        // Removing a group removes its resources.
+        assert_eq!(
+            expand_resources(&list(&["ci-full", "queues", "- queues"]), &groups),
+            set(&["ci-full", "db", "gpu"])
+        );
+        assert_eq!(expand_resources(&list(&["-gpu"]), &groups), set(&[]));
+        // With every resource available, the removed resources are kept to say they are not.
+        assert_eq!(
+            expand_resources(&list(&["*", "-gpu"]), &groups),
+            set(&["*", "-gpu"])
+        );
+        assert_eq!(
+            expand_resources(&list(&["-ci-full", "db", "*"]), &groups),
+            set(&["*", "-ci-full", "-db", "-gpu"])
+        );
+    }
+
+    #[test]
+    fn resource_group_cycles_are_expanded_once() {
+        let groups = read_resource_groups(&list(&["a=b;db", "b=c", "c=a;redis"]));
+        assert_eq!(
+            expand_resources(&list(&["a"]), &groups),
+            set(&["a", "b", "c", "db", "redis"])
+        );
+        let groups = read_resource_groups(&list(&["self=self;db"]));
+        assert_eq!(
+            expand_resources(&list(&["self"]), &groups),
+            set(&["self", "db"])
+        );
+    }
+
+    #[test]
+    fn min_updated_defaults_when_unset() {
+        let default = NaiveDate::from_ymd_opt(2023, 10, 10);
+        assert_eq!(read_min_updated(None).ok(), default);
+        assert_eq!(read_min_updated(Some(" ")).ok(), default);
+    }
+
+    #[test]
+    fn min_updated_is_read() {
+        assert_eq!(
+            read_min_updated(Some("2020-01-31")).ok(),
+            NaiveDate::from_ymd_opt(2020, 1, 31)
+        );
+        assert!(read_min_updated(Some("2020-02-30")).is_err());
+        assert!(read_min_updated(Some("last year")).is_err());
+    }
+
+    #[test]
+    fn priority_is_read() {
+        assert_eq!(read_priority("TEST_KIND_MIN_PRIORITY", None), Ok(None));
+        assert_eq!(read_priority("TEST_KIND_MIN_PRIORITY", Some(" ")), Ok(None));
+        assert_eq!(
+            read_priority("TEST_KIND_MIN_PRIORITY", Some("2")),
+            Ok(Some(2))
+        );
+        assert_eq!(
+            read_priority("TEST_KIND_DEFAULT_PRIORITY", Some(" -1 ")),
+            Ok(Some(-1))
+        );
+        assert_eq!(
+            read_priority("TEST_KIND_MIN_PRIORITY", Some("high")),
+            Err("TEST_KIND_MIN_PRIORITY=high is invalid, it must be a whole number".into())
+        );
+    }
+
+    #[test]
+    fn quarantine_mode_is_read() {
+        assert_eq!(read_quarantine(None), Ok(QuarantineMode::Report));
+        assert_eq!(read_quarantine(Some("")), Ok(QuarantineMode::Report));
+        assert_eq!(read_quarantine(Some("report")), Ok(QuarantineMode::Report));
+        assert_eq!(
+            read_quarantine(Some(" Enforce ")),
+            Ok(QuarantineMode::Enforce)
+        );
+        assert!(read_quarantine(Some("strict")).is_err());
+    }
+
+    #[test]
+    fn bench_harness_is_read() {
+        assert_eq!(read_bench_harness(None), Ok(BenchHarness::Bench));
+        assert_eq!(read_bench_harness(Some("")), Ok(BenchHarness::Bench));
+        assert_eq!(read_bench_harness(Some("bench")), Ok(BenchHarness::Bench));
+        assert_eq!(
+            read_bench_harness(Some(" Criterion ")),
+            Ok(BenchHarness::Criterion)
+        );
+        assert!(read_bench_harness(Some("divan")).is_err());
+    }
+
+    #[test]
+    fn skip_mode_is_read() {
+        assert_eq!(read_skip_mode(None), Ok(SkipMode::Ignore));
+        assert_eq!(read_skip_mode(Some(" ")), Ok(SkipMode::Ignore));
+        assert_eq!(read_skip_mode(Some("ignore")), Ok(SkipMode::Ignore));
+        assert_eq!(read_skip_mode(Some("Drop")), Ok(SkipMode::Drop));
+        assert!(read_skip_mode(Some("hide")).is_err());
+    }
+
+    #[test]
+    fn updated_policy_is_read() {
+        assert_eq!(read_updated_policy(None), Ok(UpdatedPolicy::Any));
+        assert_eq!(read_updated_policy(Some(" ")), Ok(UpdatedPolicy::Any));
+        assert_eq!(read_updated_policy(Some("any")), Ok(UpdatedPolicy::Any));
+        assert_eq!(
+            read_updated_policy(Some("Business_Days")),
+            Ok(UpdatedPolicy::BusinessDays)
+        );
+        assert!(read_updated_policy(Some("weekdays")).is_err());
+    }
+
+    #[test]
+    fn weekend_dates_are_only_rejected_for_business_days() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+        // 2024-01-06 is a Saturday, 2024-01-07 a Sunday and 2024-01-08 a Monday.
+        assert_eq!(
+            UpdatedPolicy::BusinessDays.check("updated", date(6)),
+            Err(
+                "`updated=2024-01-06` is a Saturday, but `TEST_KIND_UPDATED_POLICY=business_days` \
+                 only allows dates from Monday to Friday"
+                    .to_owned()
+            )
+        );
+        assert!(UpdatedPolicy::BusinessDays
+            .check("updated", date(7))
+            .is_err());
+        assert_eq!(
+            UpdatedPolicy::BusinessDays.check("updated", date(8)),
+            Ok(())
+        );
+        assert_eq!(UpdatedPolicy::Any.check("updated", date(6)), Ok(()));
+        assert_eq!(UpdatedPolicy::Any.check("updated", date(7)), Ok(()));
+    }
+
+    #[test]
+    fn kind_counts_are_read() {
+        let vars = [
+            ("TEST_KIND_MIN_RESOURCES_end2end", "2"),
+            ("TEST_KIND_MIN_RESOURCES_api", " 1 "),
+            ("TEST_KIND_MAX_RESOURCES_end2end", "5"),
+            ("PATH", "/bin"),
+        ]
+        .map(|(env_var, value)| (env_var.to_owned(), value.to_owned()));
+        assert_eq!(
+            read_kind_counts(vars.clone().into_iter(), "TEST_KIND_MIN_RESOURCES_"),
+            Ok(vec![("end2end".into(), 2), ("api".into(), 1)])
+        );
+        assert_eq!(
+            read_kind_counts(vars.into_iter(), "TEST_KIND_MAX_RESOURCES_"),
+            Ok(vec![("end2end".into(), 5)])
+        );
+        let invalid = [("TEST_KIND_MIN_RESOURCES_api".to_owned(), "two".to_owned())];
+        assert_eq!(
+            read_kind_counts(invalid.into_iter(), "TEST_KIND_MIN_RESOURCES_"),
+            Err("TEST_KIND_MIN_RESOURCES_api=two is invalid, it must be a whole number".into())
+        );
+    }
+
+    #[test]
+    fn config_is_displayed() {
+        let config = Config {
+            exclude: list(&["unit"]),
+            include: Vec::new(),
+            unit_age: 365,
+            unit_skip: 30,
+            exclude_resources: list(&["gpu"]),
+            defined: list(&["e2e", "api"]),
+            known_resources: Vec::new(),
+            available_resources: list(&["db", "redis"]),
+            profile: Some("staging".to_owned()),
+        };
+        assert_eq!(
+            config.to_string(),
+            "TEST_KIND_EXCLUDE: unit\n\
+             TEST_KIND_INCLUDE: (all)\n\
+             TEST_KIND_UNIT_AGE: 365 days\n\
+             TEST_KIND_UNIT_SKIP: 30 days\n\
+             TEST_KIND_EXCLUDE_RESOURCE: gpu\n\
+             TEST_KIND_DEFINED: e2e, api\n\
+             TEST_KIND_KNOWN_RESOURCES: (all)\n\
+             TEST_KIND_PROFILE: staging\n\
+             Available resources: db, redis"
+        );
+    }
+
+    #[test]
+    fn missing_resource_mode_defaults_to_skip() {
+        assert_eq!(
+            read_missing_resource_mode(None, false),
+            Ok(MissingResourceMode::Skip)
+        );
+        assert_eq!(
+            read_missing_resource_mode(Some(""), false),
+            Ok(MissingResourceMode::Skip)
+        );
+        assert_eq!(
+            read_missing_resource_mode(Some("SKIP"), false),
+            Ok(MissingResourceMode::Skip)
+        );
+        assert_eq!(
+            read_missing_resource_mode(Some(" drop "), false),
+            Ok(MissingResourceMode::Drop)
+        );
+        assert!(read_missing_resource_mode(Some("ignore"), false).is_err());
+    }
+
+    #[test]
+    fn required_resources_always_fail() {
+        assert_eq!(
+            read_missing_resource_mode(Some("Fail"), false),
+            Ok(MissingResourceMode::Fail)
+        );
+        assert_eq!(
+            read_missing_resource_mode(None, true),
+            Ok(MissingResourceMode::Fail)
+        );
+        assert_eq!(
+            read_missing_resource_mode(Some("drop"), true),
+            Ok(MissingResourceMode::Fail)
+        );
+        assert!(read_missing_resource_mode(Some("ignore"), true).is_err());
+    }
+
+    #[test]
+    fn group_members_are_expanded_in_order() {
+        let groups = read_resource_groups(&list(&["ci-full=db;queues;s3", "queues=redis;kafka"]));
+        assert_eq!(
+            group_members("ci-full", &groups),
+            Some(list(&["db", "redis", "kafka", "s3"]))
+        );
+        assert_eq!(
+            group_members("CI-Full", &groups),
+            Some(list(&["db", "redis", "kafka", "s3"]))
+        );
+        assert_eq!(group_members("db", &groups), None);
+
+        let groups = read_resource_groups(&list(&["a=b;db", "b=c;db", "c=a;redis"]));
+        assert_eq!(group_members("a", &groups), Some(list(&["redis", "db"])));
+    }
+
+    #[test]
+    fn log_mode_defaults_to_off() {
+        assert_eq!(read_log_mode(None), Ok(LogMode::Off));
+        assert_eq!(read_log_mode(Some(" ")), Ok(LogMode::Off));
+        assert_eq!(read_log_mode(Some("JSON")), Ok(LogMode::Json));
+        assert_eq!(read_log_mode(Some("pretty")), Ok(LogMode::Pretty));
+        assert!(read_log_mode(Some("color")).is_err());
+    }
+
+    #[test]
+    fn log_lines_are_formatted_by_mode() {
+        let fields = [
+            ("name", Some("my_test")),
+            ("decision", Some("skip")),
+            ("reason", Some("Test of kind: e2e requires [\"db\"]")),
+        ];
+        assert_eq!(log_line(LogMode::Off, "decision", "e2e", &fields), None);
+        assert_eq!(
+            log_line(LogMode::Json, "decision", "e2e", &fields).as_deref(),
+            Some(
+                r#"{"event":"decision","kind":"e2e","name":"my_test","decision":"skip","reason":"Test of kind: e2e requires [\"db\"]"}"#
+            )
+        );
+        assert_eq!(
+            log_line(LogMode::Pretty, "decision", "e2e", &fields).as_deref(),
+            Some(
+                r#"[e2e] decision: name="my_test" decision="skip" reason="Test of kind: e2e requires [\"db\"]""#
+            )
+        );
+
+        let run = [("name", Some("my_test")), ("reason", None)];
+        assert_eq!(
+            log_line(LogMode::Json, "decision", "unit", &run).as_deref(),
+            Some(r#"{"event":"decision","kind":"unit","name":"my_test","reason":null}"#)
+        );
+        assert_eq!(
+            log_line(LogMode::Pretty, "decision", "unit", &run).as_deref(),
+            Some(r#"[unit] decision: name="my_test""#)
+        );
+    }
+
+    #[test]
+    fn empty_attributes_use_the_default() {
+        assert_eq!(
+            attributes_or_default("", Some("integration")).as_deref(),
+            Some("integration")
+        );
+        assert_eq!(
+            attributes_or_default("  ", Some("e2e, resources=db")).as_deref(),
+            Some("e2e, resources=db")
+        );
+        assert_eq!(attributes_or_default("", None), None);
+        assert_eq!(
+            attributes_or_default("unit, updated=2024-01-01", Some("integration")).as_deref(),
+            Some("unit, updated=2024-01-01")
+        );
+        assert_eq!(
+            attributes_or_default("api, resources=db", None).as_deref(),
+            Some("api, resources=db")
+        );
+    }
+
+    /// A file in the temp dir with the `contents`, named for the test using it.
+    fn temp_file(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("test_kind_{}_{name}", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn resources_are_read_from_a_file() {
+        let path = temp_file("resources", "db, redis\n\nkafka\n postgres=16 \n");
+        let resources = read_resources_file(path.to_str(), false);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            resources,
+            Ok(list(&["db", "redis", "kafka", "postgres=16"]))
+        );
+
+        let available = expand_resources(&[list(&["gpu"]), resources.unwrap()].concat(), &[]);
+        assert_eq!(
+            available,
+            list(&["gpu", "db", "redis", "kafka", "postgres=16"])
+                .into_iter()
+                .collect()
+        );
+    }
+
+    #[test]
+    fn missing_resource_files_are_only_an_error_when_required() {
+        let missing = std::env::temp_dir().join("test_kind_missing_resources_file");
+        assert_eq!(read_resources_file(missing.to_str(), false), Ok(Vec::new()));
+        assert_eq!(read_resources_file(None, false), Ok(Vec::new()));
+        assert_eq!(read_resources_file(Some(" "), false), Ok(Vec::new()));
+
+        let err = read_resources_file(missing.to_str(), true).unwrap_err();
+        assert!(
+            err.starts_with(&format!(
+                "TEST_KIND_RESOURCES_FILE={} can not be read: ",
+                missing.display()
+            )),
+            "{err}"
+        );
+        assert_eq!(
+            read_resources_file(None, true),
+            Err(
+                "TEST_KIND_RESOURCES_FILE_REQUIRED=1, but TEST_KIND_RESOURCES_FILE is not set"
+                    .to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn the_first_matching_pattern_is_found() {
+        let patterns = list(&["unit", "api-*", "API-V1"]);
+        assert_eq!(matching_pattern(false, &patterns, "api-v1"), Some("api-*"));
+        assert_eq!(matching_pattern(true, &patterns, "API-V1"), Some("API-V1"));
+        assert_eq!(matching_pattern(false, &patterns, "Unit"), Some("unit"));
+        assert_eq!(matching_pattern(true, &patterns, "Unit"), None);
+        assert_eq!(matching_pattern(false, &patterns, "e2e"), None);
+    }
+
+    #[test]
+    fn built_in_kinds_are_always_defined() {
+        let defined = list(&["end2end"]);
+        assert!(kind_is_defined(&defined, true, "end2end"));
+        assert!(!kind_is_defined(&defined, true, "e2e"));
+        assert!(!kind_is_defined(&defined, true, "End2End"));
+        assert!(kind_is_defined(&defined, false, "End2End"));
+        for kind in ["unit", "integration", "bench", "manual"] {
+            assert!(kind_is_defined(&defined, true, kind));
+        }
+        assert!(kind_is_defined(&[], true, "e2e"));
+    }
+
+    #[test]
+    fn unsatisfied_resources_keep_their_declared_order() {
+        let resources: Vec<ResourceReq> = ["zookeeper", "db", "cache", "net", "api"]
+            .iter()
+            .map(|resource| ResourceReq::parse(resource).unwrap())
+            .collect();
+        let missing: Vec<String> = unsatisfied_resources(&resources, &set(&["net"]))
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(missing, list(&["zookeeper", "db", "cache", "api"]));
+    }
+
+    #[test]
+    fn a_wildcard_makes_every_resource_available() {
+        let groups = read_resource_groups(&list(&["ci-full=db;redis"]));
+        assert_eq!(expand_resources(&list(&["*"]), &groups), set(&["*"]));
+        assert_eq!(
+            expand_resources(&list(&["db", "*", "ci-full"]), &groups),
+            set(&["*"])
+        );
+
+        let resources: Vec<ResourceReq> = ["db", "postgres>=14", "mysql|sqlite", "!mock_server"]
+            .iter()
+            .map(|resource| ResourceReq::parse(resource).unwrap())
+            .collect();
+        // Forbidden resources are available too, so they are never satisfied.
+        let forbidden = vec![ResourceReq::parse("!mock_server").unwrap()];
+        assert_eq!(unsatisfied_resources(&resources, &set(&["*"])), forbidden);
+        assert_eq!(
+            unsatisfied_resources(&resources, &expand_resources(&list(&["db", "*"]), &[])),
+            forbidden
+        );
+        assert!(unsatisfied_resources(&resources[..3], &set(&["*"])).is_empty());
+
+        // Every resource but those removed is available.
+        let available = expand_resources(&list(&["*", "-postgres", "-mock_server"]), &[]);
+        let missing: Vec<String> = unsatisfied_resources(&resources, &available)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(missing, list(&["postgres>=14"]));
+        let available = expand_resources(&list(&["*", "-mysql"]), &[]);
+        assert_eq!(unsatisfied_resources(&resources[..3], &available), vec![]);
+        assert_eq!(unsatisfied_resources(&resources, &set(&["db"])).len(), 2);
+    }
+
+    #[test]
+    fn sample_percent_is_read() {
+        assert_eq!(read_sample_percent(None), Ok(None));
+        assert_eq!(read_sample_percent(Some(" ")), Ok(None));
+        assert_eq!(read_sample_percent(Some(" 10 ")), Ok(Some(10)));
+        assert_eq!(read_sample_percent(Some("0")), Ok(Some(0)));
+        assert_eq!(read_sample_percent(Some("100")), Ok(Some(100)));
+        assert!(read_sample_percent(Some("101")).is_err());
+        assert!(read_sample_percent(Some("-1")).is_err());
+        assert!(read_sample_percent(Some("ten")).is_err());
+    }
+
+    #[test]
+    fn all_excludes_every_kind() {
+        let excluded = list(&["e2e", "all"]);
+        for kind in [
+            "unit",
+            "integration",
+            "bench",
+            "manual",
+            "end2end",
+            "api-v1",
+        ] {
+            assert_eq!(excluded_kind_by(false, &excluded, kind), Some("all"));
+            assert_eq!(excluded_kind_by(true, &excluded, kind), Some("all"));
+        }
+        assert_eq!(
+            excluded_kind_by(false, &list(&["ALL"]), "unit"),
+            Some("ALL")
+        );
+        assert_eq!(excluded_kind_by(true, &list(&["ALL"]), "unit"), None);
+        assert_eq!(excluded_kind_by(true, &list(&["ALL"]), "ALL"), Some("ALL"));
+
+        let excluded = list(&["e2e", "api-*"]);
+        assert_eq!(excluded_kind_by(false, &excluded, "api-v1"), Some("api-*"));
+        assert_eq!(excluded_kind_by(false, &excluded, "unit"), None);
+        assert_eq!(excluded_kind_by(false, &[], "unit"), None);
+    }
+}
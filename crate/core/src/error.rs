@@ -0,0 +1,97 @@
+//! Errors in the attributes of a test, which fail it to compile.
+
+use std::fmt;
+
+use syn::Error;
+
+/// What is wrong with the attributes given to the `test_kind` macro.
+///
+/// Each is displayed as the message the test fails to compile with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestKindError {
+    /// A date is invalid, or is outside of the dates allowed.
+    InvalidDate(String),
+    /// The kind is not in `TEST_KIND_DEFINED`.
+    UndefinedKind {
+        /// The kind which is not defined.
+        kind: String,
+    },
+    /// Resources are not in `TEST_KIND_KNOWN_RESOURCES`.
+    UnknownResources {
+        /// The resources which are not known.
+        resources: Vec<String>,
+    },
+    /// A resource is given more than once.
+    DuplicateResource,
+    /// A kind is given more than once.
+    DuplicateKind,
+    /// The list of resources is invalid for any other reason.
+    InvalidResources(String),
+    /// The attributes are invalid for any other reason, such as an unknown option.
+    InvalidAttributes(String),
+}
+
+impl TestKindError {
+    /// The error the test fails to compile with, spanning its `attributes`.
+    pub(crate) fn spanned(&self, attributes: &String) -> Error {
+        Error::new_spanned(attributes, self)
+    }
+}
+
+impl fmt::Display for TestKindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TestKindError::InvalidDate(message)
+            | TestKindError::InvalidResources(message)
+            | TestKindError::InvalidAttributes(message) => write!(f, "{message}"),
+            TestKindError::UndefinedKind { kind } => write!(f, "Undefined Test Kind: {kind}"),
+            TestKindError::UnknownResources { resources } => {
+                write!(f, "Unknown Resources: {resources:?}")
+            }
+            TestKindError::DuplicateResource => {
+                write!(f, "Resources may not be specified multiple times")
+            }
+            TestKindError::DuplicateKind => {
+                write!(f, "Test kinds may not be specified multiple times")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TestKindError;
+
+    #[test]
+    fn errors_keep_their_compile_messages() {
+        let attributes = "e2e, resources=db".to_owned();
+        let message = |err: TestKindError| err.spanned(&attributes).to_string();
+
+        assert_eq!(
+            message(TestKindError::UndefinedKind {
+                kind: "e2e".to_owned()
+            }),
+            "Undefined Test Kind: e2e"
+        );
+        assert_eq!(
+            message(TestKindError::UnknownResources {
+                resources: vec!["db".to_owned()]
+            }),
+            "Unknown Resources: [\"db\"]"
+        );
+        assert_eq!(
+            message(TestKindError::DuplicateResource),
+            "Resources may not be specified multiple times"
+        );
+        assert_eq!(
+            message(TestKindError::DuplicateKind),
+            "Test kinds may not be specified multiple times"
+        );
+        assert_eq!(
+            message(TestKindError::InvalidDate(
+                "Invalid date format: x".to_owned()
+            )),
+            "Invalid date format: x"
+        );
+    }
+}
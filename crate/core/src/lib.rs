@@ -0,0 +1,162 @@
+//! Shared implementation of the `test_kind` crate.
+//!
+//! This is used by the `test_kind` macros, which decide what to do with each test as it
+//! is compiled, and by the runtime functions of the `test_kind` crate, so both make the
+//! same decisions. It is not intended to be used directly.
+
+pub mod attribute_kind;
+pub mod config;
+pub mod error;
+pub mod report;
+mod resource;
+pub mod summary;
+mod unit_age;
+
+pub use attribute_kind::{AttributeKind, SkipCategory, SkipReason, TestSettings};
+pub use config::Config;
+pub use error::TestKindError;
+
+use attribute_kind::TestAttributes;
+use resource::ResourceReq;
+use std::collections::HashSet;
+
+/// What to do with a test, decided by the current `TEST_KIND_*` environment variables.
+///
+/// This makes the same decision as the `test_kind` macro, for custom test harnesses.
+///
+/// * `kind`: The kind of test, such as `unit`, `integration` or `end2end`.
+/// * `updated`: The date the test was last updated, required for `unit` tests.
+/// * `resources`: The resources the test requires, required for other kinds of tests.
+///
+/// Any warnings about the test, such as a `unit` test which is about to age out, are added
+/// to `warnings`.
+///
+/// # Errors
+///
+/// Returns every problem if the test is not valid for its kind, each as a `TestKindError`,
+/// so they can be matched on.
+pub fn decision_for(
+    kind: &str,
+    updated: Option<&str>,
+    resources: &[&str],
+    warnings: &mut Vec<String>,
+) -> Result<TestSettings, Vec<TestKindError>> {
+    let kind = AttributeKind::from_decision(kind, updated, resources)?;
+    Ok(TestAttributes::from_kind(kind).what_to_do(warnings))
+}
+
+/// The configuration read from the current `TEST_KIND_*` environment variables.
+///
+/// This is the configuration `decision_for` uses, which can be printed to understand
+/// why tests are run or skipped.
+#[must_use]
+pub fn dump_config() -> Config {
+    Config::from_env()
+}
+
+/// Is the `resource` available, by the current `TEST_KIND_RESOURCES` environment variables?
+///
+/// This lets a test choose what to do for itself, such as which connection string to use.
+/// Resources are checked as they are when it is called, with groups in
+/// `TEST_KIND_RESOURCE_GROUPS` expanded, and may have a version or alternatives,
+/// such as `postgres>=14` or `postgres|mysql`.
+///
+/// When `TEST_KIND_RESOURCES=*`, every valid resource is available, but those removed with `-`,
+/// so no forbidden resource, such as `!mock_server`, is unless it is removed.
+/// Returns `false` if the resource is not a valid resource.
+///
+/// # Panics
+///
+/// Panics if `TEST_KIND_RESOURCES_FILE` is set, but can not be read, or an available resource
+/// has an invalid name.
+#[must_use]
+pub fn is_resource_available(resource: &str) -> bool {
+    resource_is_available(resource, &config::current_available_resources())
+}
+
+/// Is the `resource` one of the `available` resources?
+fn resource_is_available(resource: &str, available: &HashSet<String>) -> bool {
+    ResourceReq::parse(resource)
+        .is_ok_and(|resource| config::is_requirement_satisfied(&resource, available))
+}
+
+/// Is the `resource` one of the `known` resources?
+///
+/// This is used by the `test_kind` macro to check resources against the known resources
+/// registered in the code, when the test is compiled.
+/// Names are matched exactly when `case_sensitive`, otherwise ASCII case is ignored.
+#[must_use]
+pub const fn is_known_resource(known: &[&str], resource: &str, case_sensitive: bool) -> bool {
+    let resource = resource.as_bytes();
+    let mut index = 0;
+    while index < known.len() {
+        let name = known[index].as_bytes();
+        if name.len() == resource.len() {
+            let mut byte = 0;
+            while byte < name.len()
+                && (name[byte] == resource[byte]
+                    || (!case_sensitive && name[byte].eq_ignore_ascii_case(&resource[byte])))
+            {
+                byte += 1;
+            }
+            if byte == name.len() {
+                return true;
+            }
+        }
+        index += 1;
+    }
+    false
+}
+
+/// What a test returns when it skips itself with `skip_if!`, so it passes.
+///
+/// This lets `skip_if!` return from tests which return `()` or a `Result`.
+pub trait Skipped {
+    /// The value which passes the test.
+    fn skipped() -> Self;
+}
+
+impl Skipped for () {
+    fn skipped() -> Self {}
+}
+
+impl<T: Skipped, E> Skipped for Result<T, E> {
+    fn skipped() -> Self {
+        Ok(T::skipped())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resource_is_available;
+    use std::collections::HashSet;
+
+    #[test]
+    fn resources_are_available_by_name_version_or_alternative() {
+        let available: HashSet<String> = ["redis", "postgres=16"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert!(resource_is_available("redis", &available));
+        assert!(resource_is_available(" redis ", &available));
+        assert!(resource_is_available("postgres>=14", &available));
+        assert!(resource_is_available("mysql|postgres", &available));
+        assert!(!resource_is_available("postgres>16", &available));
+        assert!(!resource_is_available("mysql", &available));
+        assert!(!resource_is_available("", &available));
+        assert!(!resource_is_available("not a resource", &available));
+
+        let all: HashSet<String> = HashSet::from(["*".to_owned()]);
+        assert!(resource_is_available("mysql", &all));
+        assert!(resource_is_available("postgres>16", &all));
+        assert!(resource_is_available("mock_server", &all));
+        assert!(!resource_is_available("!mock_server", &all));
+        assert!(!resource_is_available("not a resource", &all));
+
+        let all_but_redis: HashSet<String> = HashSet::from(["*".to_owned(), "-redis".to_owned()]);
+        assert!(!resource_is_available("redis", &all_but_redis));
+        assert!(resource_is_available("!redis", &all_but_redis));
+        assert!(resource_is_available("redis|mysql", &all_but_redis));
+    }
+}
@@ -0,0 +1,216 @@
+//! Report of what was done with each test, for CI dashboards.
+
+use std::fmt::Write as _;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::attribute_kind::{SkipCategory, SkipReason, TestSettings};
+use crate::config::{TEST_KIND_REPORT, TEST_KIND_SUMMARY};
+
+/// Quote a string as a JSON string.
+pub(crate) fn json_string(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            // Writing to a `String` can not fail.
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", u32::from(c));
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Format the report line for a single test, whose `kind` is `None` when it is not known.
+fn report_line(
+    name: &str,
+    kind: Option<&str>,
+    since: Option<&str>,
+    owners: &[String],
+    settings: &TestSettings,
+) -> String {
+    let reason = settings
+        .reason()
+        .map_or_else(|| "null".to_owned(), |reason| json_string(&reason));
+    let kind = kind.map_or_else(|| "null".to_owned(), json_string);
+    let since = since.map_or_else(|| "null".to_owned(), json_string);
+    let owners: Vec<String> = owners.iter().map(|owner| json_string(owner)).collect();
+    let owners = owners.join(",");
+
+    format!(
+        "{{\"name\":{},\"kind\":{kind},\"since\":{since},\"owners\":[{owners}],\"decision\":{},\"reason\":{reason}}}\n",
+        json_string(name),
+        json_string(settings.decision()),
+    )
+}
+
+/// Append a `line` to the file at `path`, with a single write.
+///
+/// Tests are expanded in parallel, so each line must be written with a single append.
+fn append_line(path: &str, line: &str) -> io::Result<()> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| {
+            file.write_all(line.as_bytes())?;
+            file.flush()
+        })
+}
+
+/// Append what was done with a test to the `TEST_KIND_REPORT` file, if it is set,
+/// and count it in the `TEST_KIND_SUMMARY` file, if that is set.
+pub fn report(
+    name: &str,
+    kind: &str,
+    since: Option<&str>,
+    owners: &[String],
+    settings: &TestSettings,
+) {
+    if let Some(path) = TEST_KIND_REPORT.as_ref() {
+        let line = report_line(name, Some(kind), since, owners, settings);
+        if let Err(err) = append_line(path, &line) {
+            eprintln!("Failed to write test_kind report to {path}: {err}");
+        }
+    }
+
+    // The summary only needs to count each decision.
+    if let Some(path) = TEST_KIND_SUMMARY.as_ref() {
+        let line = format!("{}\n", settings.decision());
+        if let Err(err) = append_line(path, &line) {
+            eprintln!("Failed to write test_kind summary to {path}: {err}");
+        }
+    }
+}
+
+/// Report a test which skipped itself with `skip_if!` while it was running.
+///
+/// The `reason` is printed on stderr, and appended to the `TEST_KIND_REPORT` file if it is set,
+/// after the line written when the test was compiled. The test's kind is not known at runtime,
+/// so it is `null`. It is not counted in the `TEST_KIND_SUMMARY` file, which counts what was
+/// decided when the test was compiled.
+/// The test is named by its thread, which the standard test harness names after the test.
+pub fn report_runtime_skip(reason: &str) {
+    let thread = std::thread::current();
+    let name = thread.name().unwrap_or("unknown");
+    let reason = SkipReason::new(SkipCategory::Runtime, reason);
+    eprintln!("{} {name}: {}", reason.prefix(), reason.text);
+
+    if let Some(path) = TEST_KIND_REPORT.as_ref() {
+        let line = report_line(name, None, None, &[], &TestSettings::Skip { reason });
+        if let Err(err) = append_line(path, &line) {
+            eprintln!("Failed to write test_kind report to {path}: {err}");
+        }
+    }
+}
+
+/// Format the totals of the decisions in a `TEST_KIND_SUMMARY` file, one per line,
+/// such as `test_kind: 42 run, 7 skipped, 3 ignored, 0 failed`.
+///
+/// Lines which are not a decision are not counted.
+#[must_use]
+pub fn summary_totals(summary: &str) -> String {
+    let count = |decision: &str| {
+        summary
+            .lines()
+            .filter(|line| line.trim() == decision)
+            .count()
+    };
+    format!(
+        "test_kind: {} run, {} skipped, {} ignored, {} failed",
+        count("run"),
+        count("skip"),
+        count("ignore"),
+        count("fail")
+    )
+}
+
+/// Read the totals of the decisions in the `TEST_KIND_SUMMARY` file at `path`,
+/// see `summary_totals`.
+///
+/// # Errors
+///
+/// Returns an error if the file can not be read.
+pub fn read_summary_totals(path: impl AsRef<Path>) -> io::Result<String> {
+    fs::read_to_string(path).map(|summary| summary_totals(&summary))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{json_string, read_summary_totals, report_line, summary_totals};
+    use crate::attribute_kind::{SkipCategory, TestSettings};
+
+    #[test]
+    fn json_strings_are_escaped() {
+        assert_eq!(json_string("plain"), r#""plain""#);
+        assert_eq!(json_string(r#"a "b" \c"#), r#""a \"b\" \\c""#);
+        assert_eq!(json_string("a\nb\u{1}"), r#""a\nb\u0001""#);
+    }
+
+    #[test]
+    fn report_lines_include_the_reason() {
+        assert_eq!(
+            report_line("my_test", Some("unit"), None, &[], &TestSettings::Run),
+            "{\"name\":\"my_test\",\"kind\":\"unit\",\"since\":null,\"owners\":[],\"decision\":\"run\",\"reason\":null}\n"
+        );
+        assert_eq!(
+            report_line(
+                "my_test",
+                Some("e2e"),
+                Some("1.4.0"),
+                &["team-data".to_owned(), "alice".to_owned()],
+                &TestSettings::skip(
+                    SkipCategory::MissingResources,
+                    "Test of kind: e2e requires [\"db\"]"
+                )
+            ),
+            "{\"name\":\"my_test\",\"kind\":\"e2e\",\"since\":\"1.4.0\",\"owners\":[\"team-data\",\"alice\"],\"decision\":\"skip\",\"reason\":\"[test_kind:missing_resources] Test of kind: e2e requires [\\\"db\\\"]\"}\n"
+        );
+        assert_eq!(
+            report_line(
+                "my_test",
+                None,
+                None,
+                &[],
+                &TestSettings::skip(SkipCategory::Runtime, "no GPU")
+            ),
+            "{\"name\":\"my_test\",\"kind\":null,\"since\":null,\"owners\":[],\"decision\":\"skip\",\"reason\":\"[test_kind:runtime] no GPU\"}\n"
+        );
+    }
+
+    #[test]
+    fn summaries_count_each_decision() {
+        assert_eq!(
+            summary_totals("run\nskip\nrun\nignore\n\nrun\nfail\nunknown\n"),
+            "test_kind: 3 run, 1 skipped, 1 ignored, 1 failed"
+        );
+        assert_eq!(
+            summary_totals(""),
+            "test_kind: 0 run, 0 skipped, 0 ignored, 0 failed"
+        );
+    }
+
+    #[test]
+    fn summaries_are_read_from_their_file() {
+        let path =
+            std::env::temp_dir().join(format!("test_kind_summary_{}.txt", std::process::id()));
+        std::fs::write(&path, "run\nskip\n").unwrap();
+        let totals = read_summary_totals(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            totals.ok().as_deref(),
+            Some("test_kind: 1 run, 1 skipped, 0 ignored, 0 failed")
+        );
+        assert!(read_summary_totals(&path).is_err());
+    }
+}
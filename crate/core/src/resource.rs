@@ -0,0 +1,406 @@
+//! Resources required by tests.
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+
+/// How the version of an available resource is compared to a required version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VersionOp {
+    /// `>=`, at least the version.
+    AtLeast,
+    /// `>`, newer than the version.
+    Above,
+    /// `=`, exactly the version.
+    Exactly,
+}
+
+impl VersionOp {
+    /// The operator as it is written.
+    fn as_str(self) -> &'static str {
+        match self {
+            VersionOp::AtLeast => ">=",
+            VersionOp::Above => ">",
+            VersionOp::Exactly => "=",
+        }
+    }
+}
+
+/// A version a resource must have, such as `>=14`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct VersionReq {
+    /// How the available version is compared.
+    op: VersionOp,
+    /// The version as it was written.
+    version: String,
+}
+
+impl VersionReq {
+    /// Is the `available` version of the resource acceptable?
+    fn matches(&self, available: &str) -> bool {
+        let (Some(available), Some(required)) =
+            (parse_version(available), parse_version(&self.version))
+        else {
+            return false;
+        };
+        let ordering = compare_versions(&available, &required);
+        match self.op {
+            VersionOp::AtLeast => ordering != Ordering::Less,
+            VersionOp::Above => ordering == Ordering::Greater,
+            VersionOp::Exactly => ordering == Ordering::Equal,
+        }
+    }
+}
+
+/// Parse a version made of dot separated numbers, such as `14` or `16.2`.
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    version
+        .trim()
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect()
+}
+
+/// Compare two versions, where missing trailing numbers are `0`, so `16` equals `16.0`.
+fn compare_versions(version: &[u64], other: &[u64]) -> Ordering {
+    let len = version.len().max(other.len());
+    let number = |version: &[u64], index: usize| version.get(index).copied().unwrap_or(0);
+    (0..len)
+        .map(|index| number(version, index).cmp(&number(other, index)))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Split an available resource into its name and version, such that `postgres=16`
+/// becomes `("postgres", Some("16"))`.
+fn available_version(available: &str) -> (&str, Option<&str>) {
+    match available.split_once('=') {
+        Some((name, version)) => (name.trim(), Some(version.trim())),
+        None => (available, None),
+    }
+}
+
+/// Check the name of a resource is made of only ASCII letters, numbers, `_` and `-`.
+///
+/// Returns an error message if the name is empty, or has any other characters.
+fn check_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        Err("Resource names can not be empty".to_owned())
+    } else if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        Err(format!(
+            "Invalid resource name `{name}`, it may only contain letters, numbers, `_` and `-`"
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Check the name of an available resource, such as `postgres` or `postgres=16`, is a valid
+/// resource name, so it can be required.
+///
+/// Returns an error message if the name is invalid.
+pub(crate) fn check_available_name(available: &str) -> Result<(), String> {
+    check_name(available_version(available.trim()).0)
+}
+
+/// A named resource, which may need a particular version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resource {
+    /// Name of the resource.
+    name: String,
+    /// Version the resource must have, any if `None`.
+    version: Option<VersionReq>,
+}
+
+impl Resource {
+    /// Parse a resource, such as `postgres` or `postgres>=14`.
+    ///
+    /// Returns an error message if the name is invalid, or the version is not made of
+    /// dot separated numbers.
+    fn parse(resource_str: &str) -> Result<Self, String> {
+        let resource_str = resource_str.trim();
+        let Some(index) = resource_str.find(['>', '=']) else {
+            check_name(resource_str)?;
+            return Ok(Resource {
+                name: resource_str.to_owned(),
+                version: None,
+            });
+        };
+
+        let (name, constraint) = resource_str.split_at(index);
+        let name = name.trim();
+        check_name(name)?;
+        let (op, version) = if let Some(version) = constraint.strip_prefix(">=") {
+            (VersionOp::AtLeast, version)
+        } else if let Some(version) = constraint.strip_prefix('>') {
+            (VersionOp::Above, version)
+        } else {
+            (VersionOp::Exactly, &constraint[1..])
+        };
+        let version = version.trim();
+        if parse_version(version).is_none() {
+            return Err(format!(
+                "Invalid version for resource `{resource_str}`, it must be numbers separated by `.`"
+            ));
+        }
+
+        Ok(Resource {
+            name: name.to_owned(),
+            version: Some(VersionReq {
+                op,
+                version: version.to_owned(),
+            }),
+        })
+    }
+
+    /// Is this available resource, such as `postgres=16`, acceptable?
+    ///
+    /// An available resource without a version only satisfies resources without a version.
+    fn accepts(&self, available: &str) -> bool {
+        let (name, version) = available_version(available);
+        name == self.name
+            && match (&self.version, version) {
+                (None, _) => true,
+                (Some(required), Some(version)) => required.matches(version),
+                (Some(_), None) => false,
+            }
+    }
+
+    /// Is the resource one of the `available` resources, with an acceptable version?
+    fn is_available(&self, available: &HashSet<String>) -> bool {
+        available.iter().any(|available| self.accepts(available))
+    }
+
+    /// The available resources with the same name, but which are not acceptable.
+    fn unacceptable<'a>(&self, available: &'a HashSet<String>) -> Vec<&'a str> {
+        let mut unacceptable: Vec<&str> = available
+            .iter()
+            .map(String::as_str)
+            .filter(|available| {
+                available_version(available).0 == self.name && !self.accepts(available)
+            })
+            .collect();
+        unacceptable.sort_unstable();
+        unacceptable
+    }
+}
+
+impl fmt::Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.version {
+            Some(required) => write!(
+                f,
+                "{}{}{}",
+                self.name,
+                required.op.as_str(),
+                required.version
+            ),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// A resource required by a test.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceReq {
+    /// A single resource, which must be available.
+    Single(Resource),
+    /// A group of alternative resources, at least one of which must be available.
+    AnyOf(Vec<Resource>),
+    /// A single resource, which must not be available.
+    Forbidden(Resource),
+}
+
+impl ResourceReq {
+    /// Parse a resource requirement.
+    ///
+    /// Alternative resources are separated by `|`, such that `postgres|mysql`
+    /// requires either `postgres` or `mysql`.
+    /// Forbidden resources are prefixed with `!`, such that `!mock_server` requires
+    /// that `mock_server` is not available.
+    /// Required resources may have a version, such that `postgres>=14` requires
+    /// `postgres` with a version of at least 14.
+    ///
+    /// Returns an error message if a name or version is invalid, or a forbidden resource
+    /// has a version.
+    pub(crate) fn parse(resource_str: &str) -> Result<Self, String> {
+        if let Some(name) = resource_str.trim().strip_prefix('!') {
+            let resource = Resource::parse(name)?;
+            if resource.version.is_some() {
+                return Err(format!(
+                    "Forbidden resource `{}` can not have a version",
+                    resource_str.trim()
+                ));
+            }
+            return Ok(ResourceReq::Forbidden(resource));
+        }
+
+        let resources = resource_str
+            .split('|')
+            .map(Resource::parse)
+            .collect::<Result<Vec<Resource>, String>>()?;
+
+        Ok(match <[Resource; 1]>::try_from(resources) {
+            Ok([resource]) => ResourceReq::Single(resource),
+            Err(resources) => ResourceReq::AnyOf(resources),
+        })
+    }
+
+    /// The resources in the requirement.
+    fn resources(&self) -> &[Resource] {
+        match self {
+            ResourceReq::Single(resource) | ResourceReq::Forbidden(resource) => {
+                std::slice::from_ref(resource)
+            }
+            ResourceReq::AnyOf(resources) => resources,
+        }
+    }
+
+    /// The names of every resource in the requirement, without their versions.
+    pub(crate) fn names(&self) -> Vec<&String> {
+        self.resources()
+            .iter()
+            .map(|resource| &resource.name)
+            .collect()
+    }
+
+    /// The name of a single required resource, which does not need a version.
+    pub(crate) fn plain_name(&self) -> Option<&String> {
+        match self {
+            ResourceReq::Single(resource) if resource.version.is_none() => Some(&resource.name),
+            _ => None,
+        }
+    }
+
+    /// Is the resource forbidden, rather than required?
+    pub(crate) fn is_forbidden(&self) -> bool {
+        matches!(self, ResourceReq::Forbidden(_))
+    }
+
+    /// Is the requirement satisfied by the `available` resources?
+    ///
+    /// Available resources may have a version, such as `postgres=16`.
+    pub(crate) fn is_satisfied(&self, available: &HashSet<String>) -> bool {
+        match self {
+            ResourceReq::Forbidden(resource) => !resource.is_available(available),
+            _ => self
+                .resources()
+                .iter()
+                .any(|resource| resource.is_available(available)),
+        }
+    }
+
+    /// Describe the requirement when it is not satisfied by the `available` resources,
+    /// including any versions which are available but not acceptable.
+    pub(crate) fn describe_missing(&self, available: &HashSet<String>) -> String {
+        let unacceptable: Vec<&str> = self
+            .resources()
+            .iter()
+            .flat_map(|resource| resource.unacceptable(available))
+            .collect();
+        if unacceptable.is_empty() {
+            self.to_string()
+        } else {
+            format!("{self} (available: {})", unacceptable.join(", "))
+        }
+    }
+}
+
+impl fmt::Display for ResourceReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let resources: Vec<String> = self.resources().iter().map(ToString::to_string).collect();
+        write!(f, "{}", resources.join("|"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ResourceReq;
+    use std::collections::HashSet;
+
+    fn available(resources: &[&str]) -> HashSet<String> {
+        resources
+            .iter()
+            .map(|&resource| resource.to_owned())
+            .collect()
+    }
+
+    fn satisfied(resource: &str, resources: &[&str]) -> bool {
+        ResourceReq::parse(resource)
+            .unwrap()
+            .is_satisfied(&available(resources))
+    }
+
+    #[test]
+    fn resources_without_a_version_match_any_version() {
+        assert!(satisfied("postgres", &["postgres"]));
+        assert!(satisfied("postgres", &["postgres=16"]));
+        assert!(!satisfied("postgres", &["mysql=8"]));
+        assert!(!satisfied("!postgres", &["postgres=16"]));
+        assert!(satisfied("!postgres", &["mysql"]));
+    }
+
+    #[test]
+    fn versions_are_compared() {
+        assert!(satisfied("postgres>=14", &["postgres=16"]));
+        assert!(satisfied("postgres>=14", &["postgres=14.0"]));
+        assert!(!satisfied("postgres>=14", &["postgres=13.9"]));
+        assert!(satisfied("postgres>14", &["postgres=14.1"]));
+        assert!(!satisfied("postgres>14", &["postgres=14"]));
+        assert!(satisfied("postgres=16", &["postgres=16.0"]));
+        assert!(!satisfied("postgres=16", &["postgres=16.1"]));
+        assert!(satisfied("mysql>=8|postgres>=14", &["postgres=15"]));
+    }
+
+    #[test]
+    fn versions_are_required_to_be_available() {
+        assert!(!satisfied("postgres>=14", &["postgres"]));
+        assert!(!satisfied("postgres>=14", &["postgres=latest"]));
+    }
+
+    #[test]
+    fn invalid_versions_are_errors() {
+        assert!(ResourceReq::parse("postgres>=").is_err());
+        assert!(ResourceReq::parse("postgres>=fourteen").is_err());
+        assert!(ResourceReq::parse("postgres>=14.x").is_err());
+        assert!(ResourceReq::parse("!postgres>=14").is_err());
+        assert!(ResourceReq::parse("postgres >= 14.2").is_ok());
+    }
+
+    #[test]
+    fn missing_resources_describe_the_available_versions() {
+        let resources = available(&["postgres=13", "redis"]);
+        let describe = |resource: &str| {
+            ResourceReq::parse(resource)
+                .unwrap()
+                .describe_missing(&resources)
+        };
+
+        assert_eq!(
+            describe("postgres>=14"),
+            "postgres>=14 (available: postgres=13)"
+        );
+        assert_eq!(describe("redis>=7"), "redis>=7 (available: redis)");
+        assert_eq!(describe("kafka"), "kafka");
+    }
+
+    #[test]
+    fn invalid_names_are_errors() {
+        assert!(ResourceReq::parse("").is_err());
+        assert!(ResourceReq::parse(" ").is_err());
+        assert!(ResourceReq::parse("!").is_err());
+        assert!(ResourceReq::parse("postgres|").is_err());
+        assert!(ResourceReq::parse(">=14").is_err());
+        assert!(ResourceReq::parse("my service").is_err());
+        assert!(ResourceReq::parse("db;redis").is_err());
+        assert!(ResourceReq::parse("pöstgres").is_err());
+        assert_eq!(
+            ResourceReq::parse("db.main").err().as_deref(),
+            Some("Invalid resource name `db.main`, it may only contain letters, numbers, `_` and `-`")
+        );
+        assert!(ResourceReq::parse("Mock_Server-2").is_ok());
+    }
+}
@@ -0,0 +1,159 @@
+//! Summary of what was done with each test, for custom test binaries.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use crate::config::names_match_with;
+
+/// What happened to a test in a custom test binary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// The test ran and passed.
+    Passed,
+    /// The test ran and failed.
+    Failed,
+    /// The test was skipped, or could not be run.
+    Skipped,
+    /// The test was silently ignored.
+    Ignored,
+}
+
+impl Outcome {
+    /// Position of the outcome in each row of the summary table.
+    const fn column(self) -> usize {
+        match self {
+            Outcome::Passed => 0,
+            Outcome::Failed => 1,
+            Outcome::Skipped => 2,
+            Outcome::Ignored => 3,
+        }
+    }
+}
+
+/// Format a table of how many tests of each kind passed, failed, were skipped or ignored.
+///
+/// Kinds are sorted by name, and followed by the total of every kind.
+#[must_use]
+pub fn summary_table<'a>(outcomes: impl IntoIterator<Item = (&'a str, Outcome)>) -> String {
+    let mut counts: BTreeMap<&str, [usize; 4]> = BTreeMap::new();
+    let mut total = [0; 4];
+    for (kind, outcome) in outcomes {
+        counts.entry(kind).or_default()[outcome.column()] += 1;
+        total[outcome.column()] += 1;
+    }
+
+    let width = counts
+        .keys()
+        .map(|kind| kind.len())
+        .chain(["total".len()])
+        .max()
+        .unwrap_or_default();
+    let mut table = String::new();
+    // Writing to a `String` can not fail.
+    let _ = writeln!(
+        table,
+        "{:<width$}  {:>7}  {:>7}  {:>7}  {:>7}",
+        "kind", "passed", "failed", "skipped", "ignored"
+    );
+    for (kind, [passed, failed, skipped, ignored]) in counts.into_iter().chain([("total", total)]) {
+        let _ = writeln!(
+            table,
+            "{kind:<width$}  {passed:>7}  {failed:>7}  {skipped:>7}  {ignored:>7}"
+        );
+    }
+    table
+}
+
+/// The `defined` kinds which are not any of the `used` kinds.
+///
+/// A test of multiple kinds uses each of them, so `used` kinds may be comma separated.
+/// Names are matched exactly when `case_sensitive`, otherwise ASCII case is ignored.
+#[must_use]
+pub fn unused_kinds<'a, 'b>(
+    defined: &[&'a str],
+    used: impl IntoIterator<Item = &'b str>,
+    case_sensitive: bool,
+) -> Vec<&'a str> {
+    let used: Vec<&str> = used.into_iter().flat_map(|kind| kind.split(',')).collect();
+    defined
+        .iter()
+        .copied()
+        .filter(|kind| {
+            !used
+                .iter()
+                .any(|used| names_match_with(case_sensitive, kind, used.trim()))
+        })
+        .collect()
+}
+
+/// Every distinct kind of the `used` kinds, in order of their names.
+///
+/// A test of multiple kinds uses each of them, so `used` kinds may be comma separated.
+#[must_use]
+pub fn distinct_kinds<'a>(used: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut kinds: Vec<String> = used
+        .into_iter()
+        .flat_map(|kind| kind.split(','))
+        .map(str::trim)
+        .filter(|kind| !kind.is_empty())
+        .map(ToOwned::to_owned)
+        .collect();
+    kinds.sort();
+    kinds.dedup();
+    kinds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{distinct_kinds, summary_table, unused_kinds, Outcome};
+
+    #[test]
+    fn outcomes_are_counted_by_kind() {
+        let table = summary_table([
+            ("unit", Outcome::Passed),
+            ("e2e", Outcome::Skipped),
+            ("unit", Outcome::Failed),
+            ("unit", Outcome::Passed),
+            ("integration", Outcome::Ignored),
+        ]);
+        assert_eq!(
+            table,
+            concat!(
+                "kind          passed   failed  skipped  ignored\n",
+                "e2e                0        0        1        0\n",
+                "integration        0        0        0        1\n",
+                "unit               2        1        0        0\n",
+                "total              2        1        1        1\n",
+            )
+        );
+    }
+
+    #[test]
+    fn no_outcomes_only_have_a_total() {
+        assert_eq!(
+            summary_table([]),
+            "kind    passed   failed  skipped  ignored\ntotal        0        0        0        0\n"
+        );
+    }
+
+    #[test]
+    fn unused_kinds_are_found() {
+        let defined = ["e2e", "api", "Load"];
+        assert_eq!(unused_kinds(&defined, ["unit", "api,e2e"], false), ["Load"]);
+        assert_eq!(unused_kinds(&defined, ["E2E", "load"], false), ["api"]);
+        assert_eq!(
+            unused_kinds(&defined, ["E2E", "load"], true),
+            ["e2e", "api", "Load"]
+        );
+        assert!(unused_kinds(&[], ["unit"], true).is_empty());
+    }
+
+    #[test]
+    fn kinds_are_listed_once() {
+        assert_eq!(
+            distinct_kinds(["unit", "e2e,api", "unit", "api, load"]),
+            ["api", "e2e", "load", "unit"]
+        );
+        assert!(distinct_kinds([]).is_empty());
+    }
+}
@@ -0,0 +1,564 @@
+//! Unit Test Aging control
+
+use chrono::NaiveDate;
+use std::env;
+
+use crate::config::today;
+
+/// Defines the aging parameters for unit tests.
+pub(crate) struct UnitAge {
+    /// Number of days a unit test runs for in CI
+    max: u32,
+    /// Number of days after which a unit test warns that it is aging, never if 0.
+    warn: u32,
+    /// Number of days a unit test is skipped
+    skip: u32,
+    /// Do tests age out, or are they always young?
+    enforced: bool,
+}
+
+/// What to do with a unit test based on its age.
+pub(crate) enum UnitAgeResult {
+    /// Unit test is young enough to run.
+    Young,
+    /// Unit test still runs, but warns with a msg that it will soon age out.
+    Warn(String),
+    /// Unit test is aged out, but skips with a msg.
+    Aged(String),
+    /// Unit test is too old. Ignored silently.
+    Old,
+}
+
+impl UnitAgeResult {
+    /// A warning that a unit test `updated` on that date had already aged out when it was
+    /// compiled, such as when it was copied from an older test without updating its date.
+    pub(crate) fn stale_warning(&self, updated: NaiveDate) -> Option<String> {
+        match self {
+            UnitAgeResult::Young | UnitAgeResult::Warn(_) => None,
+            UnitAgeResult::Aged(_) | UnitAgeResult::Old => Some(format!(
+                "Unit test has already aged out, as it was updated on {updated}. \
+                 Was it copied from another test without updating `updated`?"
+            )),
+        }
+    }
+}
+
+/// Parse the number of days in an env var.
+///
+/// Returns `None` if the env var is not set or is empty, and an error message if it is
+/// set to something which is not a number of days.
+fn parse_days(env_var: &str, value: Option<&str>) -> Result<Option<u32>, String> {
+    match value.map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(value) => value.parse().map(Some).map_err(|_| {
+            format!("{env_var}={value} is invalid, it must be a whole number of days")
+        }),
+    }
+}
+
+/// Is the value of an env var set to something which means true?
+///
+/// Empty values, `0` and `false` are false, anything else is true.
+fn is_truthy(value: &str) -> bool {
+    let value = value.trim();
+    !(value.is_empty() || value == "0" || value.eq_ignore_ascii_case("false"))
+}
+
+/// Do tests age out, given the values of `TEST_KIND_ENFORCE_AGE` and `CI`?
+///
+/// Tests only age out in CI, unless `TEST_KIND_ENFORCE_AGE` is set, which forces
+/// age-out on when true, or off when false.
+fn read_enforce_age(enforce_age: Option<&str>, ci: Option<&str>) -> bool {
+    match (enforce_age, ci) {
+        (Some(enforce_age), _) if !enforce_age.trim().is_empty() => is_truthy(enforce_age),
+        (_, Some(ci)) => is_truthy(ci),
+        (_, None) => false,
+    }
+}
+
+/// Do tests age out, according to the `TEST_KIND_ENFORCE_AGE` and `CI` env vars?
+fn enforce_age_from_env() -> bool {
+    read_enforce_age(
+        env::var("TEST_KIND_ENFORCE_AGE").ok().as_deref(),
+        env::var("CI").ok().as_deref(),
+    )
+}
+
+/// Parse the `UnitAge` settings of integration tests, from the values of
+/// `TEST_KIND_INTEGRATION_AGE` and `TEST_KIND_INTEGRATION_SKIP`.
+///
+/// Returns `None` when there is no maximum `age`, and an error message if either is not a
+/// number of days.
+fn read_integration_age(
+    age: Option<&str>,
+    skip: Option<&str>,
+    enforced: bool,
+) -> Result<Option<UnitAge>, String> {
+    let Some(max) = parse_days("TEST_KIND_INTEGRATION_AGE", age)? else {
+        return Ok(None);
+    };
+    let skip = parse_days("TEST_KIND_INTEGRATION_SKIP", skip)?.unwrap_or(30);
+
+    Ok(Some(UnitAge {
+        max,
+        warn: 0,
+        skip,
+        enforced,
+    }))
+}
+
+/// Read the number of days in an env var, or `default` if it is not set.
+///
+/// Panics if the env var is set, but is not a number of days, so a misconfiguration
+/// fails the build rather than being silently replaced by the default.
+pub(crate) fn days_from_env(env_var: &str, default: u32) -> u32 {
+    let value = env::var(env_var).ok();
+    parse_days(env_var, value.as_deref())
+        .unwrap_or_else(|err| panic!("{err}"))
+        .unwrap_or(default)
+}
+
+impl UnitAge {
+    /// Maximum number of days a unit test runs for in CI, or 0 if they never age out.
+    pub(crate) fn max(&self) -> u32 {
+        self.max
+    }
+
+    /// Number of days a unit test is skipped, once it has aged out.
+    pub(crate) fn skip(&self) -> u32 {
+        self.skip
+    }
+
+    /// Read the `UnitAge` settings from env vars.
+    ///
+    /// * `TEST_KIND_UNIT_AGE` - Maximum number of days a unit test runs for in CI.
+    /// * `TEST_KIND_UNIT_WARN` - Number of days after which a unit test warns that it is aging.
+    /// * `TEST_KIND_UNIT_SKIP` - Number of days the unit test will show as skipped when it ages out.
+    ///
+    /// Returns the `UnitAge` structure.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either env var is set, but is not a number of days.
+    pub(crate) fn from_env() -> UnitAge {
+        let max = days_from_env("TEST_KIND_UNIT_AGE", 365);
+        let warn = days_from_env("TEST_KIND_UNIT_WARN", 0);
+        let skip = days_from_env("TEST_KIND_UNIT_SKIP", 30);
+
+        UnitAge {
+            max,
+            warn,
+            skip,
+            enforced: enforce_age_from_env(),
+        }
+    }
+
+    /// Read the `UnitAge` settings of other kinds of tests from env vars.
+    ///
+    /// * `TEST_KIND_AGE_OTHER_<kind>` - Maximum number of days a test of the kind runs for in CI.
+    /// * `TEST_KIND_SKIP_OTHER_<kind>` - Number of days the test will show as skipped when it ages out.
+    ///
+    /// Returns each kind with a maximum age, and its `UnitAge` structure.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the env vars are set, but are not a number of days.
+    pub(crate) fn other_kinds_from_env() -> Vec<(String, UnitAge)> {
+        env::vars()
+            .filter_map(|(env_var, value)| {
+                let kind = env_var.strip_prefix("TEST_KIND_AGE_OTHER_")?;
+                let max =
+                    parse_days(&env_var, Some(&value)).unwrap_or_else(|err| panic!("{err}"))?;
+                let skip = days_from_env(&format!("TEST_KIND_SKIP_OTHER_{kind}"), 30);
+
+                Some((
+                    kind.to_owned(),
+                    UnitAge {
+                        max,
+                        warn: 0,
+                        skip,
+                        enforced: enforce_age_from_env(),
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    /// Read the `UnitAge` settings of integration tests from env vars.
+    ///
+    /// * `TEST_KIND_INTEGRATION_AGE` - Maximum number of days an integration test runs for in CI.
+    /// * `TEST_KIND_INTEGRATION_SKIP` - Number of days the test will show as skipped when it ages out.
+    ///
+    /// Returns `None` when there is no maximum age, as integration tests do not age out by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either env var is set, but is not a number of days.
+    pub(crate) fn integration_from_env() -> Option<UnitAge> {
+        read_integration_age(
+            env::var("TEST_KIND_INTEGRATION_AGE").ok().as_deref(),
+            env::var("TEST_KIND_INTEGRATION_SKIP").ok().as_deref(),
+            enforce_age_from_env(),
+        )
+        .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// The settings for a single test, which overrides the maximum age and the number
+    /// of days it is skipped with its own `age` and `skip`, when given.
+    pub(crate) fn with_overrides(&self, max: Option<u32>, skip: Option<u32>) -> UnitAge {
+        UnitAge {
+            max: max.unwrap_or(self.max),
+            warn: self.warn,
+            skip: skip.unwrap_or(self.skip),
+            enforced: self.enforced,
+        }
+    }
+
+    /// Is the unit test too old?
+    ///
+    /// Given the `since` date, returns a `UnitAgeResult`.
+    pub(crate) fn unit_aged_out(&self, since: NaiveDate) -> UnitAgeResult {
+        self.unit_aged_out_at(since, today())
+    }
+
+    /// Is the unit test too old, on the date `now`?
+    ///
+    /// Given the `since` date, returns a `UnitAgeResult`.
+    fn unit_aged_out_at(&self, since: NaiveDate, now: NaiveDate) -> UnitAgeResult {
+        // Always young if Unit Max age is 0, or age-out is not enforced.
+        if self.max == 0 || !self.enforced {
+            return UnitAgeResult::Young;
+        }
+        let age = now.signed_duration_since(since).num_days();
+        // Both are `u32`, so neither their sum nor the age in days can overflow an `i64`.
+        let silent_age = i64::from(self.max) + i64::from(self.skip);
+        if self.warn > 0 && age >= self.warn.into() && age < self.max.into() {
+            let skip_in = i64::from(self.max).saturating_sub(age);
+            // Without any days to be skipped, the test is silenced as soon as it ages out.
+            let action = if self.skip == 0 {
+                "Silenced"
+            } else {
+                "Skipped"
+            };
+            UnitAgeResult::Warn(format!("{action} in {skip_in} days"))
+        } else if age < self.max.into() {
+            UnitAgeResult::Young
+        } else {
+            let skip_left = silent_age.saturating_sub(age);
+            if skip_left > 0 {
+                UnitAgeResult::Aged(format!("Silenced in {skip_left} days"))
+            } else {
+                UnitAgeResult::Old
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse_days, read_enforce_age, read_integration_age, today, UnitAge, UnitAgeResult,
+    };
+    use chrono::{Days, NaiveDate};
+
+    #[test]
+    fn unset_or_empty_days_use_the_default() {
+        assert_eq!(parse_days("TEST_KIND_UNIT_AGE", None), Ok(None));
+        assert_eq!(parse_days("TEST_KIND_UNIT_AGE", Some("")), Ok(None));
+        assert_eq!(parse_days("TEST_KIND_UNIT_AGE", Some("  ")), Ok(None));
+    }
+
+    #[test]
+    fn valid_days_are_parsed() {
+        assert_eq!(parse_days("TEST_KIND_UNIT_AGE", Some("180")), Ok(Some(180)));
+        assert_eq!(parse_days("TEST_KIND_UNIT_SKIP", Some(" 0 ")), Ok(Some(0)));
+    }
+
+    #[test]
+    fn invalid_days_are_errors() {
+        assert_eq!(
+            parse_days("TEST_KIND_UNIT_AGE", Some("abc")),
+            Err("TEST_KIND_UNIT_AGE=abc is invalid, it must be a whole number of days".into())
+        );
+        assert!(parse_days("TEST_KIND_UNIT_SKIP", Some("-1")).is_err());
+        assert!(parse_days("TEST_KIND_UNIT_SKIP", Some("1.5")).is_err());
+    }
+
+    #[test]
+    fn age_is_enforced_in_ci() {
+        assert!(read_enforce_age(None, Some("true")));
+        assert!(read_enforce_age(None, Some("1")));
+        assert!(read_enforce_age(Some(""), Some("true")));
+    }
+
+    #[test]
+    fn age_is_not_enforced_outside_ci() {
+        assert!(!read_enforce_age(None, None));
+        assert!(!read_enforce_age(None, Some("")));
+        assert!(!read_enforce_age(None, Some("false")));
+        assert!(!read_enforce_age(Some(" "), Some("0")));
+    }
+
+    #[test]
+    fn age_enforcement_can_be_forced() {
+        assert!(read_enforce_age(Some("1"), None));
+        assert!(read_enforce_age(Some("true"), Some("false")));
+        assert!(!read_enforce_age(Some("0"), Some("true")));
+        assert!(!read_enforce_age(Some("false"), None));
+    }
+
+    #[test]
+    fn unenforced_tests_are_always_young() {
+        let old = NaiveDate::from_ymd_opt(2023, 10, 10).unwrap();
+        let age = |enforced| UnitAge {
+            max: 1,
+            warn: 0,
+            skip: 1,
+            enforced,
+        };
+        assert!(matches!(
+            age(false).unit_aged_out(old),
+            UnitAgeResult::Young
+        ));
+        assert!(matches!(age(true).unit_aged_out(old), UnitAgeResult::Old));
+    }
+
+    /// The date `days` after `since`.
+    fn days_after(since: NaiveDate, days: u64) -> NaiveDate {
+        since + Days::new(days)
+    }
+
+    #[test]
+    fn aging_tests_warn_before_they_age_out() {
+        let age = UnitAge {
+            max: 10,
+            warn: 7,
+            skip: 5,
+            enforced: true,
+        };
+        let since = NaiveDate::from_ymd_opt(2024, 2, 20).unwrap();
+        let aged_out = |days| age.unit_aged_out_at(since, days_after(since, days));
+        assert!(matches!(aged_out(6), UnitAgeResult::Young));
+        assert!(matches!(
+            aged_out(7),
+            UnitAgeResult::Warn(warning) if warning == "Skipped in 3 days"
+        ));
+        assert!(matches!(
+            aged_out(9),
+            UnitAgeResult::Warn(warning) if warning == "Skipped in 1 days"
+        ));
+        assert!(matches!(aged_out(10), UnitAgeResult::Aged(_)));
+    }
+
+    #[test]
+    fn unit_tests_age_out_at_each_boundary() {
+        let age = UnitAge {
+            max: 365,
+            warn: 0,
+            skip: 30,
+            enforced: true,
+        };
+        let since = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        let aged_out = |days| age.unit_aged_out_at(since, days_after(since, days));
+        assert!(matches!(aged_out(0), UnitAgeResult::Young));
+        assert!(matches!(aged_out(364), UnitAgeResult::Young));
+        assert!(matches!(
+            aged_out(365),
+            UnitAgeResult::Aged(reason) if reason == "Silenced in 30 days"
+        ));
+        assert!(matches!(
+            aged_out(394),
+            UnitAgeResult::Aged(reason) if reason == "Silenced in 1 days"
+        ));
+        assert!(matches!(aged_out(395), UnitAgeResult::Old));
+        assert!(matches!(aged_out(1000), UnitAgeResult::Old));
+    }
+
+    #[test]
+    fn unit_tests_without_skip_days_go_straight_to_old() {
+        let age = UnitAge {
+            max: 10,
+            warn: 0,
+            skip: 0,
+            enforced: true,
+        };
+        let since = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let aged_out = |age: &UnitAge, days| age.unit_aged_out_at(since, days_after(since, days));
+        // Young until it is `max` days old, then Old, without ever being Aged.
+        for days in 0..10 {
+            assert!(matches!(aged_out(&age, days), UnitAgeResult::Young));
+        }
+        for days in [10, 11, 100] {
+            assert!(matches!(aged_out(&age, days), UnitAgeResult::Old));
+        }
+
+        // Aging tests warn that they will be silenced, as they are never skipped.
+        let warned = UnitAge { warn: 8, ..age };
+        assert!(matches!(aged_out(&warned, 7), UnitAgeResult::Young));
+        assert!(matches!(
+            aged_out(&warned, 9),
+            UnitAgeResult::Warn(warning) if warning == "Silenced in 1 days"
+        ));
+        assert!(matches!(aged_out(&warned, 10), UnitAgeResult::Old));
+    }
+
+    #[test]
+    fn unit_tests_with_no_max_age_never_age_out() {
+        let age = UnitAge {
+            max: 0,
+            warn: 0,
+            skip: 30,
+            enforced: true,
+        };
+        let since = NaiveDate::from_ymd_opt(2023, 10, 10).unwrap();
+        assert!(matches!(
+            age.unit_aged_out_at(since, days_after(since, 10_000)),
+            UnitAgeResult::Young
+        ));
+    }
+
+    #[test]
+    fn dates_valid_today_are_young_today() {
+        // Dates are validated and aged against the same today, so valid future dates are young.
+        let age = UnitAge {
+            max: 1,
+            warn: 0,
+            skip: 0,
+            enforced: true,
+        };
+        assert!(matches!(age.unit_aged_out(today()), UnitAgeResult::Young));
+        assert!(matches!(
+            age.unit_aged_out(days_after(today(), 2)),
+            UnitAgeResult::Young
+        ));
+        assert!(matches!(
+            age.unit_aged_out(today() - Days::new(1)),
+            UnitAgeResult::Old
+        ));
+    }
+
+    #[test]
+    fn absurd_ages_do_not_panic() {
+        let age = UnitAge {
+            max: 4_000_000_000,
+            warn: u32::MAX,
+            skip: u32::MAX,
+            enforced: true,
+        };
+        let now = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+
+        assert!(matches!(
+            age.unit_aged_out_at(NaiveDate::MIN, now),
+            UnitAgeResult::Young
+        ));
+        assert!(matches!(
+            age.unit_aged_out_at(NaiveDate::MAX, now),
+            UnitAgeResult::Young
+        ));
+        assert!(matches!(
+            age.unit_aged_out_at(now, NaiveDate::MAX),
+            UnitAgeResult::Young
+        ));
+
+        let short = UnitAge {
+            max: 1,
+            warn: 0,
+            skip: u32::MAX,
+            enforced: true,
+        };
+        assert!(matches!(
+            short.unit_aged_out_at(NaiveDate::MIN, NaiveDate::MAX),
+            UnitAgeResult::Aged(_)
+        ));
+        assert!(matches!(
+            UnitAge { skip: 0, ..short }.unit_aged_out_at(NaiveDate::MIN, NaiveDate::MAX),
+            UnitAgeResult::Old
+        ));
+        assert!(matches!(
+            short.unit_aged_out_at(NaiveDate::MAX, NaiveDate::MIN),
+            UnitAgeResult::Young
+        ));
+    }
+
+    #[test]
+    fn per_test_overrides_replace_only_what_they_give() {
+        let global = UnitAge {
+            max: 365,
+            warn: 0,
+            skip: 30,
+            enforced: true,
+        };
+        let since = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let aged_out = |age: &UnitAge, days| age.unit_aged_out_at(since, days_after(since, days));
+
+        let shorter = global.with_overrides(Some(90), None);
+        assert!(matches!(aged_out(&shorter, 89), UnitAgeResult::Young));
+        assert!(matches!(
+            aged_out(&shorter, 90),
+            UnitAgeResult::Aged(reason) if reason == "Silenced in 30 days"
+        ));
+        assert!(matches!(aged_out(&global, 90), UnitAgeResult::Young));
+
+        let quieter = global.with_overrides(None, Some(7));
+        assert!(matches!(aged_out(&quieter, 364), UnitAgeResult::Young));
+        assert!(matches!(
+            aged_out(&quieter, 365),
+            UnitAgeResult::Aged(reason) if reason == "Silenced in 7 days"
+        ));
+        assert!(matches!(aged_out(&quieter, 372), UnitAgeResult::Old));
+
+        let both = global.with_overrides(Some(90), Some(7));
+        assert!(matches!(aged_out(&both, 97), UnitAgeResult::Old));
+        assert!(matches!(
+            aged_out(&global.with_overrides(Some(0), None), 1000),
+            UnitAgeResult::Young
+        ));
+    }
+
+    #[test]
+    fn only_tests_which_have_aged_out_are_stale() {
+        let age = UnitAge {
+            max: 10,
+            warn: 7,
+            skip: 5,
+            enforced: true,
+        };
+        let since = NaiveDate::from_ymd_opt(2024, 2, 20).unwrap();
+        let stale = |days| {
+            age.unit_aged_out_at(since, days_after(since, days))
+                .stale_warning(since)
+        };
+        assert_eq!(stale(0), None);
+        assert_eq!(stale(8), None, "aging tests still run");
+        assert_eq!(
+            stale(10).as_deref(),
+            Some(
+                "Unit test has already aged out, as it was updated on 2024-02-20. \
+                 Was it copied from another test without updating `updated`?"
+            )
+        );
+        assert!(stale(15).is_some(), "old tests are stale too");
+    }
+
+    #[test]
+    fn integration_tests_only_age_out_with_an_age() {
+        assert!(read_integration_age(None, Some("10"), true)
+            .unwrap()
+            .is_none());
+        assert!(read_integration_age(Some(""), None, true)
+            .unwrap()
+            .is_none());
+        assert!(read_integration_age(Some("soon"), None, true).is_err());
+        assert!(read_integration_age(Some("90"), Some("-1"), true).is_err());
+
+        let age = read_integration_age(Some("90"), None, true)
+            .unwrap()
+            .unwrap();
+        assert_eq!((age.max(), age.skip()), (90, 30));
+        let since = NaiveDate::from_ymd_opt(2024, 2, 20).unwrap();
+        let aged_out = |days| age.unit_aged_out_at(since, days_after(since, days));
+        assert!(matches!(aged_out(89), UnitAgeResult::Young));
+        assert!(matches!(aged_out(90), UnitAgeResult::Aged(_)));
+        assert!(matches!(aged_out(120), UnitAgeResult::Old));
+    }
+}
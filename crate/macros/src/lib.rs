@@ -0,0 +1,748 @@
+//! Attribute macros of the `test_kind` crate.
+//!
+//! These are re-exported by, and documented in, the `test_kind` crate.
+extern crate proc_macro;
+
+use test_kind_core::attribute_kind::{SkipReason, TestAttributes, TestSettings};
+use test_kind_core::config::{
+    attributes_or_default, debug, log_decision, BenchHarness, SkipMode, TEST_KIND_BENCH_HARNESS,
+    TEST_KIND_CASE_SENSITIVE, TEST_KIND_DEFAULT, TEST_KIND_IGNORE_PLAIN, TEST_KIND_SKIP_MODE,
+};
+#[cfg(feature = "summary")]
+use test_kind_core::dump_config;
+use test_kind_core::report::report;
+
+use proc_macro::TokenStream;
+use quote::{quote, quote_spanned};
+use std::time::Duration;
+use syn::ext::IdentExt;
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Error, Expr, ExprLit, FnArg, Ident, Item, ItemFn,
+    Lit, Meta, MetaNameValue, Path, ReturnType, Type,
+};
+
+/// Parse the item `test_kind` is applied to, which must be a free function.
+///
+/// Returns an error if it is not a function, or it is a method, which `#[test]` can not be used on.
+fn parse_test_fn(input: TokenStream) -> Result<ItemFn, Error> {
+    let test_fn = match syn::parse::<Item>(input)? {
+        Item::Fn(test_fn) => test_fn,
+        item => {
+            return Err(Error::new_spanned(
+                item,
+                "`test_kind` can only be applied to test functions",
+            ))
+        }
+    };
+    if let Some(FnArg::Receiver(receiver)) = test_fn.sig.inputs.first() {
+        let name = &test_fn.sig.ident;
+        return Err(Error::new_spanned(
+            receiver,
+            format!(
+                "`{name}`: `test_kind` can not be applied to methods, as `#[test]` can only be \
+                 used on free functions. Move it out of the `impl` block."
+            ),
+        ));
+    }
+    Ok(test_fn)
+}
+
+#[proc_macro_attribute]
+pub fn test_kind(attr: TokenStream, input: TokenStream) -> TokenStream {
+    // Parse the input tokens into a syntax tree
+    let test_fn = match parse_test_fn(input) {
+        Ok(test_fn) => test_fn,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // Parse the attribute arguments, falling back to the default kind from the environment
+    let Some(attr_str) = attributes_or_default(&attr.to_string(), TEST_KIND_DEFAULT.as_deref())
+    else {
+        return Error::new_spanned(
+            &test_fn.sig.ident,
+            "No test kind given, and `TEST_KIND_DEFAULT` is not set. Give its kind and options, \
+             such as `#[test_kind(integration)]`, `#[test_kind(unit, updated=YYYY-MM-DD)]` \
+             or `#[test_kind(<kind>, resources=<comma separated list of resources>)]`",
+        )
+        .to_compile_error()
+        .into();
+    };
+    expand_test(&attr_str, &test_fn).into()
+}
+
+#[proc_macro_attribute]
+pub fn test_kind_default(attr: TokenStream, input: TokenStream) -> TokenStream {
+    // Parse the input tokens into a syntax tree
+    let mut test_mod = parse_macro_input!(input as syn::ItemMod);
+
+    // Parse the attribute arguments, falling back to the default kind from the environment
+    let Some(attr_str) = attributes_or_default(&attr.to_string(), TEST_KIND_DEFAULT.as_deref())
+    else {
+        return Error::new_spanned(
+            &test_mod.ident,
+            "No default test kind given, and `TEST_KIND_DEFAULT` is not set",
+        )
+        .to_compile_error()
+        .into();
+    };
+
+    let Some((_, items)) = test_mod.content.as_mut() else {
+        return Error::new_spanned(
+            &test_mod,
+            "`test_kind_default` can only be applied to inline modules",
+        )
+        .to_compile_error()
+        .into();
+    };
+
+    for item in items.iter_mut() {
+        if let Item::Fn(test_fn) = item {
+            // Only plain `#[test]` functions take the default, explicit `#[test_kind]` wins.
+            let Some(index) = test_fn
+                .attrs
+                .iter()
+                .position(|attr| attr.path().is_ident("test"))
+            else {
+                continue;
+            };
+            if test_fn.attrs.iter().any(|attr| {
+                attr.path()
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "test_kind")
+            }) {
+                continue;
+            }
+
+            test_fn.attrs.remove(index);
+            *item = Item::Verbatim(expand_test(&attr_str, test_fn));
+        }
+    }
+
+    quote!(#test_mod).into()
+}
+
+#[cfg(feature = "summary")]
+#[proc_macro]
+pub fn assert_kinds_used(input: TokenStream) -> TokenStream {
+    if !input.is_empty() {
+        return Error::new(
+            proc_macro2::Span::call_site(),
+            "`assert_kinds_used!` takes no arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    // The kinds are those defined when the test is compiled, like the tests themselves.
+    let defined = dump_config().defined;
+    let case_sensitive = *TEST_KIND_CASE_SENSITIVE;
+    quote! {
+        #[test]
+        fn test_kind_defined_kinds_are_used() {
+            ::test_kind::summary::assert_kinds_used(&[#(#defined),*], #case_sensitive);
+        }
+    }
+    .into()
+}
+
+/// Maximum length of the reason a test is skipped.
+const MAX_REASON_LEN: usize = 200;
+
+/// The reason given to `#[ignore]`, which names the test and is no longer than `MAX_REASON_LEN`.
+///
+/// It still starts with the category of the reason, so it can be parsed.
+fn skip_reason(name: &Ident, reason: &SkipReason) -> String {
+    let reason = format!("{} {}: {}", reason.prefix(), name.unraw(), reason.text);
+    match reason.char_indices().nth(MAX_REASON_LEN) {
+        Some((end, _)) => format!("{}...", &reason[..end]),
+        None => reason,
+    }
+}
+
+/// The type of `Result` a test returns, or `None` if it returns `()`, so it can be wrapped.
+///
+/// Returns an error if the test is async, or returns anything else, as it can not be
+/// wrapped to `action` it.
+fn wrapped_output<'a>(test_fn: &'a ItemFn, action: &str) -> Result<Option<&'a Type>, Error> {
+    let name = &test_fn.sig.ident;
+    let output = match &test_fn.sig.output {
+        ReturnType::Default => Some(None),
+        ReturnType::Type(_, output) => match &**output {
+            Type::Path(path)
+                if path
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "Result") =>
+            {
+                Some(Some(&**output))
+            }
+            _ => None,
+        },
+    };
+    match output {
+        Some(output) if test_fn.sig.asyncness.is_none() => Ok(output),
+        _ => Err(Error::new_spanned(
+            &test_fn.sig,
+            format!(
+                "`{name}`: Only tests which are not async and return `()` or a `Result` can {action}"
+            ),
+        )),
+    }
+}
+
+/// Wrap the body of a quarantined test, so a panic, or an error it returns, is caught and
+/// reported rather than failing it.
+///
+/// Returns an error if the test can not be quarantined, because it is async, returns a value
+/// which is not a `Result`, or is expected to panic.
+fn quarantined(test_fn: &ItemFn) -> Result<ItemFn, Error> {
+    let name = &test_fn.sig.ident;
+    let output = wrapped_output(test_fn, "be quarantined")?;
+    if test_fn
+        .attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("should_panic"))
+    {
+        return Err(Error::new_spanned(
+            name,
+            format!("`{name}`: Tests which should panic can not be quarantined"),
+        ));
+    }
+
+    let mut test_fn = test_fn.clone();
+    let block = &test_fn.block;
+    let message = format!(
+        "{}: Quarantined test failed, reported instead",
+        name.unraw()
+    );
+    test_fn.block = match output {
+        None => parse_quote!({
+            if ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| #block)).is_err() {
+                ::std::eprintln!(#message);
+            }
+        }),
+        // The error is reported like a panic, and the test passes.
+        Some(output) => parse_quote!({
+            match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| -> #output #block)) {
+                ::core::result::Result::Ok(::core::result::Result::Ok(())) => {}
+                ::core::result::Result::Ok(::core::result::Result::Err(err)) => {
+                    ::std::eprintln!("{}: {:?}", #message, err);
+                }
+                ::core::result::Result::Err(_) => ::std::eprintln!(#message),
+            }
+            ::core::result::Result::Ok(())
+        }),
+    };
+    Ok(test_fn)
+}
+
+/// Wrap the body of a flaky test, so it is retried up to `retries` times before it fails.
+///
+/// A test which returns a `Result` is retried when it returns an error, as well as when it
+/// panics, and returns the result of its last attempt.
+/// Tests which should panic are not retried, as their panic is how they pass.
+fn retried(test_fn: &ItemFn, retries: u32) -> Result<ItemFn, Error> {
+    let name = &test_fn.sig.ident;
+    let output = wrapped_output(test_fn, "be retried")?;
+    if test_fn
+        .attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("should_panic"))
+    {
+        return Ok(test_fn.clone());
+    }
+
+    let mut test_fn = test_fn.clone();
+    let block = &test_fn.block;
+    let message = format!("{}: Flaky test failed, retrying", name.unraw());
+    // Every attempt but the last catches its panic, so the last fails the test.
+    // Each attempt borrows the test, as it is not `Copy` when it captures an argument.
+    test_fn.block = match output {
+        None => parse_quote!({
+            let mut test = || #block;
+            for attempt in 1..=#retries {
+                if ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(&mut test)).is_ok() {
+                    return;
+                }
+                ::std::eprintln!("{} ({}/{})", #message, attempt, #retries);
+            }
+            test()
+        }),
+        Some(output) => parse_quote!({
+            let mut test = || -> #output #block;
+            for attempt in 1..=#retries {
+                match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(&mut test)) {
+                    ::core::result::Result::Ok(::core::result::Result::Ok(())) => {
+                        return ::core::result::Result::Ok(());
+                    }
+                    ::core::result::Result::Ok(::core::result::Result::Err(err)) => {
+                        ::std::eprintln!("{} ({}/{}): {:?}", #message, attempt, #retries, err);
+                    }
+                    ::core::result::Result::Err(_) => {
+                        ::std::eprintln!("{} ({}/{})", #message, attempt, #retries);
+                    }
+                }
+            }
+            test()
+        }),
+    };
+    Ok(test_fn)
+}
+
+/// Wrap the body of a test with a timeout, so it runs on its own thread and fails if it takes
+/// longer than `timeout`.
+///
+/// A test which times out is left running in the background, as threads can not be killed.
+/// Its panics are passed on unchanged, as is the `Result` it returns.
+///
+/// Returns an error if the test can not be timed out, because it is async, takes arguments,
+/// returns a value which is not a `Result`, or is expected to panic, as timing out would pass it.
+fn timed_out(test_fn: &ItemFn, timeout: Duration) -> Result<ItemFn, Error> {
+    let name = &test_fn.sig.ident;
+    let output = wrapped_output(test_fn, "have a timeout")?;
+    if !test_fn.sig.inputs.is_empty() {
+        return Err(Error::new_spanned(
+            &test_fn.sig.inputs,
+            format!("`{name}`: Only tests which take no arguments can have a timeout"),
+        ));
+    }
+    if test_fn
+        .attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("should_panic"))
+    {
+        return Err(Error::new_spanned(
+            name,
+            format!("`{name}`: Tests which should panic can not have a timeout"),
+        ));
+    }
+
+    let mut test_fn = test_fn.clone();
+    let block = &test_fn.block;
+    let output = output.map_or_else(|| quote!(()), |output| quote!(#output));
+    let thread_name = name.unraw().to_string();
+    let message = format!(
+        "{}: Test timed out after {timeout:?}, it is left running in the background",
+        name.unraw()
+    );
+    let millis = u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX);
+    // The channel is disconnected when the test finishes, whether or not it panics.
+    test_fn.block = parse_quote!({
+        let (finished, finishing) = ::std::sync::mpsc::channel::<()>();
+        let test = ::std::thread::Builder::new()
+            .name(::std::string::String::from(#thread_name))
+            .spawn(move || -> #output {
+                let _finished = finished;
+                #block
+            })
+            .expect("Failed to start the test thread");
+        if let ::core::result::Result::Err(::std::sync::mpsc::RecvTimeoutError::Timeout) =
+            finishing.recv_timeout(::std::time::Duration::from_millis(#millis))
+        {
+            ::core::panic!(#message);
+        }
+        match test.join() {
+            ::core::result::Result::Ok(result) => result,
+            ::core::result::Result::Err(panic) => ::std::panic::resume_unwind(panic),
+        }
+    });
+    Ok(test_fn)
+}
+
+/// A criterion benchmark which is not run, whose body is replaced so it does nothing.
+///
+/// Criterion names each benchmark in `criterion_group!`, so it must still exist.
+/// Prints the `reason` it was skipped, if it was.
+fn criterion_stub(test_fn: &ItemFn, reason: Option<&str>) -> ItemFn {
+    let mut test_fn = test_fn.clone();
+    test_fn.attrs.push(parse_quote!(#[allow(unused_variables)]));
+    test_fn.block = match reason {
+        Some(reason) => parse_quote!({
+            ::std::eprintln!("{}", #reason);
+        }),
+        None => parse_quote!({}),
+    };
+    test_fn
+}
+
+/// The `#[ignore]` of the test itself, if it has one.
+fn own_ignore(test_fn: &ItemFn) -> Option<&Attribute> {
+    test_fn
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("ignore"))
+}
+
+/// The reason of an `#[ignore]`, if it has one, such as `#[ignore = "slow"]`.
+fn ignore_reason(ignore: &Attribute) -> Option<String> {
+    match &ignore.meta {
+        Meta::NameValue(MetaNameValue {
+            value:
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(reason),
+                    ..
+                }),
+            ..
+        }) => Some(reason.value()),
+        _ => None,
+    }
+}
+
+/// The `#[ignore]` given to a skipped test, which is bare when `plain`, and has its reason otherwise.
+#[must_use]
+fn ignore_attribute_with(plain: bool, reason: &str) -> Attribute {
+    if plain {
+        parse_quote!(#[ignore])
+    } else {
+        parse_quote!(#[ignore = #reason])
+    }
+}
+
+/// The `#[ignore]` given to a skipped test, according to `TEST_KIND_IGNORE_PLAIN`.
+#[must_use]
+fn ignore_attribute(reason: &str) -> Attribute {
+    ignore_attribute_with(*TEST_KIND_IGNORE_PLAIN, reason)
+}
+
+/// Can the test be run by `test_kind_main!`, without its own harness?
+fn is_plain_test(harness: &Path, test_fn: &ItemFn) -> bool {
+    harness.is_ident("test")
+        && test_fn.sig.asyncness.is_none()
+        && test_fn.sig.inputs.is_empty()
+        && test_fn.sig.generics.params.is_empty()
+        && matches!(test_fn.sig.output, ReturnType::Default)
+}
+
+/// Register the test, so `test_kind_main!` can summarize it, and run it if it is `runnable`.
+///
+/// Custom test binaries are not compiled with `--test`, so `#[test]` functions do not exist
+/// in them. A plain copy of a runnable test is registered instead, which is only in scope
+/// of the registration.
+fn registration(
+    test_fn: &ItemFn,
+    kind: &str,
+    settings: &TestSettings,
+    runnable: Option<&ItemFn>,
+) -> proc_macro2::TokenStream {
+    let name = &test_fn.sig.ident;
+    let test_name = name.unraw().to_string();
+    let decision = settings.decision();
+    // The registration only exists when the test does.
+    let cfgs = test_fn
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"));
+
+    let (plain_fn, run, should_panic) = match runnable {
+        Some(runnable) => {
+            let should_panic = runnable
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("should_panic"));
+            let mut plain_fn = runnable.clone();
+            plain_fn.attrs.retain(|attr| {
+                !attr.path().is_ident("should_panic") && !attr.path().is_ident("ignore")
+            });
+            (
+                quote!(#plain_fn),
+                quote!(::core::option::Option::Some(#name)),
+                should_panic,
+            )
+        }
+        None => (quote!(), quote!(::core::option::Option::None), false),
+    };
+
+    quote! {
+        #plain_fn
+        #(#cfgs)*
+        ::test_kind::summary::inventory::submit! {
+            ::test_kind::summary::TestRecord {
+                name: ::core::concat!(::core::module_path!(), "::", #test_name),
+                kind: #kind,
+                decision: #decision,
+                run: #run,
+                should_panic: #should_panic,
+            }
+        }
+    }
+}
+
+/// The attribute which runs the test, its `harness` if it has one, or else `#[bench]` for
+/// benchmarks and `#[test]` for every other test.
+fn harness_of(attributes: &TestAttributes) -> Path {
+    attributes.harness.clone().unwrap_or_else(|| {
+        if attributes.is_bench() {
+            parse_quote!(bench)
+        } else {
+            parse_quote!(test)
+        }
+    })
+}
+
+/// Checks that each resource of the test is known, when the test lists its `known_resources`.
+///
+/// Resources can only be checked against the known resources in the code once it compiles.
+fn resource_checks(attributes: &TestAttributes, name: &Ident) -> Option<proc_macro2::TokenStream> {
+    let known = attributes.known_resources.as_ref()?;
+    let case_sensitive = *TEST_KIND_CASE_SENSITIVE;
+    let checks = attributes
+        .unregistered_resources()
+        .into_iter()
+        .map(|resource| {
+            let message = format!(
+            "`{}`: Unknown resource `{resource}`, it is not in `{}` or `TEST_KIND_KNOWN_RESOURCES`",
+            name.unraw(),
+            quote!(#known).to_string().replace(' ', "")
+        );
+            quote_spanned! {name.span()=>
+                const _: () = ::core::assert!(
+                    ::test_kind::is_known_resource(#known, #resource, #case_sensitive),
+                    #message
+                );
+            }
+        });
+    Some(quote!(#(#checks)*))
+}
+
+/// A `warning` about the test `name`.
+///
+/// Proc macros can not emit warnings directly, so use a deprecated constant to raise them.
+fn warning_for(name: &Ident, warning: &str) -> proc_macro2::TokenStream {
+    debug(&format!("Warn {warning}"));
+    let note = format!("`{name}`: {warning}");
+    quote_spanned! {name.span()=>
+        const _: () = {
+            #[deprecated(note = #note)]
+            const TEST_KIND_WARNING: () = ();
+            TEST_KIND_WARNING
+        };
+    }
+}
+
+/// Report and log what is done with the test, and return it.
+///
+/// A test which runs keeps its own `#[ignore]`, so what is reported says it is ignored.
+fn reported(
+    test_fn: &ItemFn,
+    kind: &str,
+    since: Option<&str>,
+    owners: &[String],
+    settings: &TestSettings,
+) -> TestSettings {
+    let name = test_fn.sig.ident.to_string();
+    let reported = match own_ignore(test_fn) {
+        Some(ignore) => settings.ignored_by_test(ignore_reason(ignore).as_deref()),
+        None => settings.clone(),
+    };
+    report(&name, kind, since, owners, &reported);
+    log_decision(&name, kind, &reported);
+    reported
+}
+
+/// Wrap the test which is run with its `timeout`, `retries` and quarantine, when it
+/// `catches_panics`.
+///
+/// Each attempt of a flaky test has its own timeout, and flaky tests are retried before they
+/// are quarantined, so a quarantine only catches their last failure.
+///
+/// Returns an error if the test can not be wrapped by any of them.
+fn wrapped(
+    test_fn: &ItemFn,
+    timeout: Option<Duration>,
+    retries: u32,
+    catches_panics: bool,
+) -> Result<ItemFn, Error> {
+    if !catches_panics && retries == 0 && timeout.is_none() {
+        debug("Run");
+        return Ok(test_fn.clone());
+    }
+    debug(&format!(
+        "Run with {retries} retries{}{}",
+        timeout.map_or_else(String::new, |timeout| format!(", a timeout of {timeout:?}")),
+        if catches_panics { ", quarantined" } else { "" }
+    ));
+    let test_fn = match timeout {
+        Some(timeout) => timed_out(test_fn, timeout)?,
+        None => test_fn.clone(),
+    };
+    let test_fn = match retries {
+        0 => test_fn,
+        retries => retried(&test_fn, retries)?,
+    };
+    if catches_panics {
+        quarantined(&test_fn)
+    } else {
+        Ok(test_fn)
+    }
+}
+
+/// The registration of the test for the `summary` feature, in its own `const` block, and
+/// only with the test's `cfg` when it requires features.
+///
+/// Tests which fail to compile are never registered, and only plain tests which are run are
+/// `runnable` by `test_kind_main!`.
+fn registered(
+    test_fn: &ItemFn,
+    kind: &str,
+    reported: &TestSettings,
+    runnable: Option<&ItemFn>,
+    cfg: Option<&proc_macro2::TokenStream>,
+) -> Option<proc_macro2::TokenStream> {
+    if !cfg!(feature = "summary") || matches!(reported, TestSettings::Fail { .. }) {
+        return None;
+    }
+    let runnable = runnable.filter(|_| *reported == TestSettings::Run);
+    let registration = registration(test_fn, kind, reported, runnable);
+    Some(quote! {
+        #cfg
+        const _: () = {
+            #registration
+        };
+    })
+}
+
+/// Expand a single test function, given the `test_kind` attribute string.
+fn expand_test(attr_str: &str, test_fn: &ItemFn) -> proc_macro2::TokenStream {
+    let attributes = match TestAttributes::for_test(attr_str, &test_fn.sig.ident.to_string()) {
+        Ok(attributes) => attributes,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    // Benchmarks are run by `#[bench]`, or by criterion which has no attribute at all.
+    let criterion = attributes.is_bench()
+        && attributes.harness.is_none()
+        && *TEST_KIND_BENCH_HARNESS == BenchHarness::Criterion;
+    let harness = harness_of(&attributes);
+    let harness_attrs = attributes.harness_attributes(&harness);
+    let harness_attr = (!criterion).then(|| quote!(#(#harness_attrs)*));
+
+    let name = &test_fn.sig.ident;
+    let kind = attributes.kind_name();
+    let since = attributes.since().map(ToOwned::to_owned);
+    let owners = attributes.owners().to_vec();
+
+    let resource_checks = resource_checks(&attributes, name);
+    // Tests which require cargo features are only compiled when all of them are enabled.
+    let features = attributes.features();
+    let cfg = (!features.is_empty()).then(|| quote!(#[cfg(all(#(feature = #features),*))]));
+    let catches_panics = attributes.catches_panics();
+    let retries = attributes.retries();
+    let timeout = attributes.timeout();
+    let plain_test = is_plain_test(&harness, test_fn);
+    let mut warnings = Vec::new();
+    let settings = attributes.what_to_do(&mut warnings);
+    let reported = reported(test_fn, &kind, since.as_deref(), &owners, &settings);
+
+    let warnings = warnings.iter().map(|warning| warning_for(name, warning));
+
+    // The test function which is run, when it is run.
+    let mut runnable = None;
+    let test = match settings {
+        // Return the test function, and allow it to run, but not to fail when quarantined.
+        TestSettings::Run => match wrapped(test_fn, timeout, retries, catches_panics) {
+            Ok(test_fn) => {
+                let test = quote! {
+                    #cfg
+                    #harness_attr
+                    #test_fn
+                };
+                runnable = Some(test_fn);
+                test
+            }
+            Err(err) => err.to_compile_error(),
+        },
+        TestSettings::Ignore if criterion => {
+            debug("Ignore");
+            // Keep the benchmark, so criterion can still name it, but do nothing.
+            let test_fn = criterion_stub(test_fn, None);
+            quote! {
+                #cfg
+                #test_fn
+            }
+        }
+        TestSettings::Ignore => {
+            debug("Ignore");
+            // Return an empty TokenStream to exclude the function from the code
+            quote!()
+        }
+        TestSettings::Skip { reason } if criterion => {
+            debug(&format!("Skip {reason}"));
+            // Criterion can not ignore a benchmark, so it only says why it was skipped.
+            let reason = format!("Skipped {}", skip_reason(name, &reason));
+            let test_fn = criterion_stub(test_fn, Some(&reason));
+            quote! {
+                #cfg
+                #test_fn
+            }
+        }
+        TestSettings::Skip { reason } if *TEST_KIND_SKIP_MODE == SkipMode::Drop => {
+            debug(&format!("Skip {reason}"));
+            // Exclude the function from the code, like ignored tests.
+            quote!()
+        }
+        TestSettings::Skip { reason } => {
+            debug(&format!("Skip {reason}"));
+            let ignore = ignore_attribute(&skip_reason(name, &reason));
+            // A test can only be ignored once, so the skip reason replaces any of its own.
+            let mut test_fn = test_fn.clone();
+            test_fn.attrs.retain(|attr| !attr.path().is_ident("ignore"));
+            quote! {
+               #cfg
+               #harness_attr
+               #ignore
+               #test_fn
+            }
+        }
+        TestSettings::Fail { reason } => {
+            debug(&format!("Fail {reason}"));
+            Error::new_spanned(name, format!("`{name}`: {reason}")).to_compile_error()
+        }
+    };
+
+    let registration = registered(
+        test_fn,
+        &kind,
+        &reported,
+        runnable.as_ref().filter(|_| plain_test),
+        cfg.as_ref(),
+    );
+
+    quote! {
+        #(#warnings)*
+        #resource_checks
+        #test
+        #registration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ignore_attribute_with;
+    use syn::{Expr, ExprLit, Lit, Meta, MetaNameValue};
+
+    #[test]
+    fn ignore_attribute_is_plain_when_asked() {
+        let reason = "[test_kind:resource] `my_test`: requires \"db\"";
+        let ignore = ignore_attribute_with(false, reason);
+        match &ignore.meta {
+            Meta::NameValue(MetaNameValue {
+                path,
+                value:
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(text),
+                        ..
+                    }),
+                ..
+            }) => {
+                assert!(path.is_ident("ignore"));
+                assert_eq!(text.value(), reason);
+            }
+            _ => panic!("expected `#[ignore = \"...\"]`"),
+        }
+
+        let ignore = ignore_attribute_with(true, reason);
+        assert!(matches!(&ignore.meta, Meta::Path(path) if path.is_ident("ignore")));
+    }
+}
@@ -9,12 +9,90 @@
 //! * `integration`
 //! * everything else.
 //!
+//! Benchmarks are also a kind of their own, `bench`.
+//!
 //! All test kinds are controlled by the `TEST_KIND_*` environment variables.
 //!
 //! The `TEST_KIND_*` environment variables are:
 //!
 //! * `TEST_KIND_EXCLUDE` - A list of Test Kinds NOT to run.  
-//!     for example: `TEST_KIND_EXCLUDE=unit,integration` would exclude unit and integration tests.
+//!   for example: `TEST_KIND_EXCLUDE=unit,integration` would exclude unit and integration tests.
+//!   A `*` matches any characters, so `TEST_KIND_EXCLUDE=api-*` would exclude `api-v1` and `api-v2` tests.
+//!   `TEST_KIND_EXCLUDE=all` excludes every kind of test, to quickly disable every test in a stage.
+//!   So `all` is reserved, and should not be used as a kind. It is matched like any kind, so with
+//!   `TEST_KIND_CASE_SENSITIVE` only `all` excludes every kind.
+//!   As exclusion wins over `TEST_KIND_INCLUDE`, no test runs, even those of included kinds.
+//! * `TEST_KIND_INCLUDE` - A list of the only Test Kinds to run.
+//!   for example: `TEST_KIND_INCLUDE=unit` would skip every test which is not a unit test.
+//!   If a kind is both included and excluded, it is excluded.
+//! * `TEST_KIND_EXCLUDE_RESOURCE` - A list of resources whose tests are NOT to run, whatever their kind.
+//!   for example: `TEST_KIND_EXCLUDE_RESOURCE=gpu` would skip every test which requires `gpu`,
+//!   even if it is available. A `*` matches any characters, like `TEST_KIND_EXCLUDE`.
+//!   A test is skipped if any resource it requires, or any of its alternatives, is excluded.
+//!   Excluding a forbidden resource does not skip the test.
+//! * `TEST_KIND_EXCLUDE_ALL_OTHER` - Set to `1` to skip every test of a kind which is not built in,
+//!   whatever its kind, with the reason `all external-resource tests excluded`.
+//!   for example, on a minimal CI stage which only runs unit and integration tests.
+//!   `unit`, `integration`, `bench` and `manual` tests are unaffected.
+//! * `TEST_KIND_SAMPLE_PERCENT` - The percentage of tests to run, from 0 to 100, such as `10`
+//!   to run about one test in ten on each CI shard of a very large suite. The other tests which
+//!   would run are skipped with the reason `sampled out`.
+//!   Tests are chosen by hashing their function name with `TEST_KIND_SAMPLE_SEED`, so the same
+//!   seed always chooses the same tests. Use a different seed for each shard or run to cover
+//!   different tests. By default the seed is empty.
+//! * `TEST_KIND_PROFILE` - The profile whose resources are required, by tests which give
+//!   resources for profiles, see [Profiles](#profiles).
+//! * `TEST_KIND_CASE_SENSITIVE` - Set to `1` to match test kinds and resources exactly.
+//!   By default they are matched ignoring case, so `End2End` matches `end2end`.
+//! * `TEST_KIND_REPORT` - A file to append a JSON line to for each test, recording its
+//!   `name`, `kind`, the version it was added in (`since`), its `owners`, the `decision`
+//!   (`run`, `skip`, `ignore` or `fail`) and the `reason`.
+//!   Tests are compiled in parallel, so each line is written with a single append.
+//!   This records what was decided when each test was compiled, not whether it passed.
+//!   Only tests which are recompiled are reported, so clean the build for a full report.
+//! * `TEST_KIND_SUMMARY` - A file to append the decision for each test to, one per line, so
+//!   the totals can be printed at the end of the build with `read_summary_totals`, such as
+//!   `test_kind: 42 run, 7 skipped, 3 ignored, 0 failed`.
+//!   Like `TEST_KIND_REPORT`, only tests which are recompiled are counted, and the file is
+//!   appended to, so remove it before the build.
+//! * `TEST_KIND_SKIP_MODE` - What to do with skipped tests, either `ignore` or `drop`.
+//!   By default they are `ignore`d, and show as ignored with the reason they were skipped.
+//!   When `drop`, they are removed like tests which are silently ignored, so they are not
+//!   counted by the test harness at all.
+//! * `TEST_KIND_IGNORE_PLAIN` - Set to `1` to ignore skipped tests with a bare `#[ignore]`,
+//!   without the reason they were skipped, for tools which can not handle the reason.
+//!   The reason is still logged by `TEST_KIND_LOG` and recorded by `TEST_KIND_REPORT`.
+//! * `TEST_KIND_BENCH_HARNESS` - The harness of `bench` tests, either `bench` or `criterion`.
+//!   By default it is `bench`, see [Benchmarks](#benchmarks).
+//! * `TEST_KIND_MISSING_RESOURCE_MODE` - What to do with tests which require a resource that
+//!   is not available, either `skip`, `drop` or `fail`. By default they are `skip`ped with the
+//!   resources they require. When `drop`, they are silently ignored instead, so environments
+//!   which will never have a resource are not cluttered with skipped tests.
+//!   When `fail`, they fail to compile, so a resource which should be available in CI but
+//!   is not is found, rather than its tests being quietly skipped.
+//!   A test of multiple kinds fails if any of its kinds fail, even if another would run.
+//!   Tests which forbid an available resource are still skipped.
+//! * `TEST_KIND_REQUIRE_RESOURCES` - Set to `1` to make missing resources a compile error,
+//!   the same as `TEST_KIND_MISSING_RESOURCE_MODE=fail`, whatever that is set to.
+//! * `TEST_KIND_FORCE_RUN` - Set to `1` to run every test, whatever its kind, age, resources,
+//!   platform, priority or expiry. This defeats all of the other `TEST_KIND_*` env vars, and
+//!   is only meant for debugging locally. It should never be set in CI.
+//! * `TEST_KIND_DRY_RUN` - Set to `1` to skip every test, with a reason saying what would have
+//!   been done, so `cargo test -- --ignored --list` lists what would run in the environment.
+//!   Tests which would run are skipped as `[test_kind:dry_run] would run as <kind>`, and tests
+//!   which would be skipped anyway say so. Ignored tests are still silently ignored.
+//!   With `TEST_KIND_SKIP_MODE=drop` every test is dropped, so this should not be used with it.
+//! * `TEST_KIND_RUN_ID` - The ID of the CI run, added to the reason each test is skipped after
+//!   its token, such as `[test_kind:excluded] my_test: [run=abc123] Integration tests are ...`,
+//!   to tie skip diagnostics to the pipeline run. By default reasons have no run ID.
+//! * `TEST_KIND_DEBUG` - Set to `1` to print diagnostics about each test's kind as it is compiled.
+//! * `TEST_KIND_LOG` - Log what is decided for each test as it is compiled to stderr, as
+//!   `json` or `pretty`. It is `off` by default. Each event is a single line, so they do not
+//!   interleave when tests are compiled in parallel.
+//!   With `json` each line is an object with the `event`, `kind` and the fields of the event,
+//!   such as the `name`, `decision` and `reason` of a `decision`. Cargo reads JSON on stderr
+//!   as its own messages, so it prints each object wrapped in a message, under `message`.
+//!   With `pretty` each line starts with the `[kind]`, so sorting them groups them by kind.
 //!
 //! ## Unit Tests
 //!
@@ -25,8 +103,34 @@
 //!
 //! * `TEST_KIND_UNIT_AGE` - Maximum number of days a unit test runs for in CI.
 //! * `TEST_KIND_UNIT_SKIP` - Number of days the unit test will show as skipped when it ages out.
+//! * `TEST_KIND_UNIT_WARN` - Number of days after which a unit test warns that it is aging.
+//!   It still runs, but raises a compiler warning with the number of days until it is skipped.
+//!   It is 0 by default, which never warns. These warnings will fail builds which deny warnings.
+//!
+//! So a unit test runs from when it was updated, warns once it is `TEST_KIND_UNIT_WARN` days old,
+//! is skipped once it is `TEST_KIND_UNIT_AGE` days old, and is silently ignored once it is
+//! `TEST_KIND_UNIT_AGE` + `TEST_KIND_UNIT_SKIP` days old.
+//! Warning only happens when `TEST_KIND_UNIT_WARN` is less than `TEST_KIND_UNIT_AGE`.
+//! With `TEST_KIND_UNIT_SKIP=0` a unit test is never skipped: it runs until it is
+//! `TEST_KIND_UNIT_AGE` days old, and is silently ignored from that day on.
+//!
+//! Age-out only happens in CI, which is detected by the `CI` env var being set, as it is by
+//! most CI services. Outside of CI every unit test runs, so they do not vanish during local
+//! development. Setting `TEST_KIND_ENFORCE_AGE=1` forces age-out everywhere, and
+//! `TEST_KIND_ENFORCE_AGE=0` disables it, even in CI.
+//! This also applies to other kinds of tests which age out.
 //!
 //! Setting `TEST_KIND_UNIT_AGE` to 0 will disable unit test age-out.
+//! If either is set to something which is not a whole number of days, the build fails.
+//!
+//! Setting `TEST_KIND_WARN_AGED=1` will also raise a compiler warning for each unit test
+//! which has aged out and is being skipped, so they are noticed during `cargo build`.
+//! These warnings will fail builds which deny warnings.
+//!
+//! Setting `TEST_KIND_WARN_STALE_AUTHORING=1` raises a compiler warning for each unit test which
+//! has already aged out, whether it is skipped or ignored, when it is compiled. This catches a
+//! test copied from an older one without updating its `updated` date, which would otherwise
+//! never run. Age-out must be enforced, such as in CI, for a test to have aged out.
 //!
 //! These are specified as:
 //!
@@ -42,13 +146,88 @@
 //! ```
 //!
 //! * `updated` is in the format YYYY-MM-DD and must be a valid date no later than today and no earlier than 2023-10-10.
+//!   The earliest date can be changed with the `TEST_KIND_MIN_UPDATED` env var, also YYYY-MM-DD.
+//!   If that is not a valid date, every test with an `updated` date fails to build.
+//!   Teams which want to forbid implausible dates can set `TEST_KIND_UPDATED_POLICY=business_days`,
+//!   which rejects `updated` and `created` dates on a Saturday or Sunday, including relative
+//!   dates and dates read from env vars. It is `any` by default, which allows any date.
+//!   Today is the date in the local timezone of the build machine, for both checking and aging dates.
+//!   Dates up to 2 days in the future are allowed, to cover timezones ahead of the build machine.
+//!   This can be changed with the `TEST_KIND_FUTURE_TOLERANCE_DAYS` env var.
+//!   When scaffolding tests ahead of time, set `TEST_KIND_ALLOW_FUTURE_UPDATED=1` to allow any
+//!   date in the future. Future dates are then treated as today, so the test ages as if it was
+//!   updated today, and keeps doing so until the date passes.
+//!   It may also be a quoted RFC3339 timestamp, such as `updated="2024-03-14T09:30:00Z"`,
+//!   in which case only the date of the timestamp is used.
+//!   Or it may be relative to today, as a quoted number of days, weeks or months ago,
+//!   such as `updated="90d"`, `updated="2w"` or `updated="6mo"`.
+//!   This is mainly a convenience for trying out age-out, relative dates never age.
+//!   Or it may be read from an env var when the test is compiled, such as `updated="env:MY_DATE_VAR"`,
+//!   for tests generated by build scripts. It is an error if the env var is not set.
+//!   Cargo does not rebuild tests when the env var changes, unless a build script
+//!   prints `cargo:rerun-if-env-changed=MY_DATE_VAR`.
+//!   Or it may be `updated="git"`, to read it from an env var named after the test function,
+//!   `TEST_KIND_GIT_DATE_` followed by its name in upper case, see [Dates from Git](#dates-from-git).
+//! * `created` is optional, and is the date the test was first written.
+//!   It has the same format and limits as `updated`, and must not be after it.
+//!   It is recorded for auditing only, and does not affect age-out.
+//! * `age` and `skip` are optional, and override `TEST_KIND_UNIT_AGE` and `TEST_KIND_UNIT_SKIP`
+//!   for just this test, as a whole number of days.
+//!   Either may be given without the other, such as `#[test_kind(unit, updated="2024-01-01", age=90)]`,
+//!   and the global setting is used for the one which is not given.
+//!
+//! ### Dates from Git
+//!
+//! Rather than keeping `updated` by hand, it can be taken from git with `updated="git"`.
+//! The date is then read, when the test is compiled, from the env var `TEST_KIND_GIT_DATE_`
+//! followed by the name of the test function in upper case, so `my_test` is read from
+//! `TEST_KIND_GIT_DATE_MY_TEST`. It is an error if it is not set.
+//! Tests with the same name in different modules share the same env var.
+//!
+//! A build script sets it, such as from the last commit which changed the test file:
+//! ```rust,ignore
+//! // build.rs
+//! use std::process::Command;
+//!
+//! fn main() {
+//!     let output = Command::new("git")
+//!         .args(["log", "-1", "--format=%cs", "--", "tests/my_tests.rs"])
+//!         .output()
+//!         .expect("git is installed");
+//!     let date = String::from_utf8_lossy(&output.stdout);
+//!     for test in ["my_test", "my_other_test"] {
+//!         println!(
+//!             "cargo:rustc-env=TEST_KIND_GIT_DATE_{}={}",
+//!             test.to_uppercase(),
+//!             date.trim()
+//!         );
+//!     }
+//!     println!("cargo:rerun-if-changed=tests/my_tests.rs");
+//! }
+//! ```
+//!
+//! Then the test is given as:
+//! ```rust,ignore
+//! #[test_kind(unit, updated="git")]
+//! fn my_test() {
+//!    // Test code
+//! }
+//! ```
 //!
 //! ## Integration Tests
 //!
-//! These tests do not require any external resources, and do not age out.
+//! These tests do not require any external resources, and do not age out by default.
 //! They should be tests on larger interconnected pieces of the code.
 //!
-//! There are no specific env vars associated with these, other than `TEST_KIND_EXCLUDE`.
+//! Integration tests which also need periodic review can be given an `updated` date, with the
+//! same format as unit tests. They then age out like unit tests when a maximum age is set by:
+//!
+//! * `TEST_KIND_INTEGRATION_AGE` - Maximum number of days an integration test runs for in CI.
+//! * `TEST_KIND_INTEGRATION_SKIP` - Number of days the test will show as skipped when it ages out.
+//!   Defaults to 30.
+//!
+//! Without either the date or `TEST_KIND_INTEGRATION_AGE`, integration tests never age out.
+//! Like unit tests, a test which has aged out is skipped or ignored even if its kind is excluded.
 //!
 //! These are specified as:
 //!
@@ -61,6 +240,55 @@
 //! fn my_test() {
 //!    // Test code
 //! }
+//!
+//! #[test_kind(integration, updated="2024-01-01")]
+//! fn my_reviewed_test() {
+//!    // Test code
+//! }
+//! ```
+//!
+//! ## Benchmarks
+//!
+//! Benchmarks do not age out either, and are run by a benchmark harness rather than `#[test]`.
+//! By default they are `#[bench]` functions, which is only available on nightly Rust, in crates
+//! with `#![feature(test)]`.
+//!
+//! These are specified as:
+//!
+//! ```rust,ignore
+//! #![feature(test)]
+//! extern crate test;
+//!
+//! use test_kind::test_kind;
+//!
+//! #[test_kind(bench)]
+//! fn my_bench(b: &mut test::Bencher) {
+//!    // Benchmark code
+//! }
+//! ```
+//!
+//! With `TEST_KIND_BENCH_HARNESS=criterion` they are plain functions instead, to be named in
+//! criterion's `criterion_group!`. Criterion can not skip or ignore a benchmark, so one which
+//! is not run still exists, but does nothing, and prints the reason when it was skipped.
+//! A `harness` option always wins over `TEST_KIND_BENCH_HARNESS`.
+//!
+//! ## Manual Tests
+//!
+//! Manual tests are too expensive, or need too much setup, to ever run in CI. They have no
+//! resources and never age out, and are always skipped with the reason
+//! `manual test, run explicitly`, unless the `TEST_KIND_RUN_MANUAL` env var is set to `1`.
+//! They can still be excluded or not included, like any other kind.
+//!
+//! These are specified as:
+//! ```rust
+//! #[macro_use]
+//!
+//! use test_kind::test_kind;
+//!
+//! #[test_kind(manual)]
+//! fn my_test() {
+//!    // Test code
+//! }
 //! ```
 //!
 //! ## Everything Else
@@ -68,9 +296,60 @@
 //! All other kinds of tests are expected to have at least 1 external resource dependency.
 //!
 //! The resources the tests can assume are present are defined as a list in the `TEST_KIND_RESOURCES` env var.
+//! They can also be listed in a file, separated by commas or newlines, whose path is in the
+//! `TEST_KIND_RESOURCES_FILE` env var. Its resources are added to those in `TEST_KIND_RESOURCES`.
+//! A file which does not exist has no resources, unless `TEST_KIND_RESOURCES_FILE_REQUIRED=1`,
+//! when every test fails to build without it. Cargo does not rebuild tests when the file changes.
+//! On a fully provisioned runner, `TEST_KIND_RESOURCES=*` makes every resource available, whatever
+//! its version, without listing them. Any other resources listed with `*` make no difference.
+//! A resource prefixed with `-` is removed, after every other resource and group is added,
+//! so resources can be composed by layers of CI configuration. For example, with the group
+//! `ci-full=db;gpu`, `TEST_KIND_RESOURCES=ci-full,-gpu` makes `ci-full` and `db` available.
+//! Removing a group removes its resources, and a resource is removed whatever its version.
+//! With `*`, every resource but those removed is available, so `TEST_KIND_RESOURCES=*,-gpu`
+//! makes every resource but `gpu` available.
+//! A resource a test forbids is available too, unless it is removed, so the test is skipped as
+//! `[test_kind:forbidden_resources]`.
 //! When any other kind of test is defined its list of necessary external resources must be supplied.
+//! Resource names may only contain letters, numbers, `_` and `-`, such as `mock_server-2`.
+//! The build fails if an available resource has any other name, such as `my service`, as no
+//! test could require it.
+//! Resources are separated by commas, whitespace or both, so `resources="db redis"` is the same
+//! as `resources="db, redis"`, which is convenient when copying them from a shell array.
+//! An empty resource in the list, such as from a trailing comma in `resources="db,redis,"`,
+//! is an error rather than being ignored.
 //! The allowed resources can be constrained with a list of known resources in the `TEST_KIND_KNOWN_RESOURCES` env var.
 //!
+//! A single resource can stand for a group of resources, listed in the `TEST_KIND_RESOURCE_GROUPS`
+//! env var as `group=resource;resource`, such as `ci-full=db;redis;kafka`.
+//! When `ci-full` is in `TEST_KIND_RESOURCES`, `db`, `redis` and `kafka` are available too.
+//! Groups can contain other groups.
+//! A test can also require a group, such as `resources=ci-full`, which requires every resource
+//! in it. The skip reason then lists the resources in the group which are missing, and only
+//! they need to be in `TEST_KIND_KNOWN_RESOURCES`. A name which is neither a group nor a known
+//! resource fails to compile. A group counts as each of its resources for
+//! `TEST_KIND_MIN_RESOURCES_<kind>` and `TEST_KIND_MAX_RESOURCES_<kind>`.
+//! Groups which are alternatives, forbidden or need a version are not expanded.
+//!
+//! Known resources can also be registered in the code once, with the `resources!` macro,
+//! and named by each test with the optional `known_resources` parameter.
+//! Attribute macros can not share state, so every test must name the constant itself,
+//! or take it from the `test_kind_default` of its module.
+//! The known resources are then the union of the constant and `TEST_KIND_KNOWN_RESOURCES`,
+//! and a test using a resource in neither fails to compile. The constant also satisfies
+//! `TEST_KIND_DEFINED`, so `TEST_KIND_KNOWN_RESOURCES` does not need to be set.
+//!
+//! ```rust
+//! use test_kind::test_kind;
+//!
+//! test_kind::resources!(KNOWN_RESOURCES = ["db", "redis"]);
+//!
+//! #[test_kind(end2end, resources=db, known_resources=KNOWN_RESOURCES)]
+//! fn my_test() {
+//!    // Test code
+//! }
+//! ```
+//!
 //! The name of the test is arbitrary but should match what kind of test it is.
 //! Examples of these kinds of tests:
 //!
@@ -81,8 +360,51 @@
 //! There is no limit to the kinds of tests, but they should be constrained by reasonableness.
 //! Projects should define a known set of tests, and what they mean to maintain consistency.
 //! These can be enforced with the `TEST_KIND_DEFINED` env var, which lists the known list of
-//! kinds of tests, `unit` and `integration` do not need to be listed.
-//! If this env var is not defined, any unit test name is allowed.
+//! kinds of tests. The built in kinds, `unit`, `integration`, `bench` and `manual`, are always
+//! defined and do not need to be listed.
+//! If this env var is not defined, any kind of test is allowed.
+//! Anything after a `#` in an item of this, or any other list, is a comment, so a long list
+//! in a dotenv file can say what its kinds are for, such as `end2end # slow, api`.
+//! When it is defined, `TEST_KIND_KNOWN_RESOURCES` must also be defined, so every resource
+//! used by the defined kinds is checked.
+//!
+//! The number of resources a test of a kind must require can be limited, with env vars
+//! named after the kind:
+//!
+//! * `TEST_KIND_MIN_RESOURCES_<kind>` - Minimum number of resources a test of the kind requires.
+//! * `TEST_KIND_MAX_RESOURCES_<kind>` - Maximum number of resources a test of the kind requires.
+//!
+//! For example `TEST_KIND_MIN_RESOURCES_end2end=2` fails to compile an `end2end` test which
+//! requires only one resource. A group of alternatives counts as one resource, and forbidden
+//! resources are not counted. Without these env vars there is no limit.
+//!
+//! Other kinds of tests do not age out, unless they are given an `updated` date and their
+//! kind has a maximum age. These are set for each kind, with env vars named after the kind:
+//!
+//! * `TEST_KIND_AGE_OTHER_<kind>` - Maximum number of days a test of the kind runs for in CI.
+//! * `TEST_KIND_SKIP_OTHER_<kind>` - Number of days the test will show as skipped when it ages out.
+//!   Defaults to 30.
+//!
+//! For example `TEST_KIND_AGE_OTHER_api=180` ages out `api` tests 180 days after they were updated.
+//! Like unit tests, a test which has aged out is skipped or ignored even if its kind is excluded.
+//!
+//! A resource can be a group of alternatives separated by `|`, in which case the test
+//! only requires one of them to be available.
+//! For example `resources=postgres|mysql, redis` requires `redis` and either `postgres` or `mysql`.
+//!
+//! A resource prefixed with `!` is forbidden, and the test only runs if it is not available.
+//! For example `resources=!mock_server, db` requires `db`, and that `mock_server` is not available.
+//! A resource can not be both required and forbidden.
+//!
+//! A required resource can also need a version, compared with `>=`, `>` or `=`.
+//! For example `resources=postgres>=14` requires `postgres` with a version of at least 14.
+//! Versions are numbers separated by `.`, and missing numbers are 0, so `16` is `16.0`.
+//! The available version is given in `TEST_KIND_RESOURCES`, such as `TEST_KIND_RESOURCES=postgres=16`.
+//! A resource without a version in `TEST_KIND_RESOURCES` does not satisfy one which needs a version,
+//! and when it is missing the reason includes the versions which are available.
+//! Resources with no version match any available version.
+//! A resource which needs exactly a version, such as `postgres=16`, looks like an option unless
+//! it is first, so quote the list of resources, such as `resources="redis, postgres=16"`.
 //!
 //! These are specified as:
 //! ```rust
@@ -95,52 +417,662 @@
 //!    // Test code
 //! }
 //! ```
-extern crate proc_macro;
-
-mod attribute_kind;
-mod config;
-mod unit_age;
+//!
+//! or with an updated date:
+//! ```rust
+//! #[macro_use]
+//!
+//! use test_kind::test_kind;
+//!
+//! #[test_kind(api, resources=foo, updated=2023-10-15)]
+//! fn my_test() {
+//!    // Test code
+//! }
+//! ```
+//!
+//! ### Profiles
+//!
+//! When the same tests are run against environments with different resources, the resources
+//! can be given for each profile, as `resources@<profile>`.
+//! The resources of the profile in the `TEST_KIND_PROFILE` env var are required, or else those
+//! given without a profile. It fails to compile if there are none for the profile, and none
+//! without one. Every list is checked, whichever profile is active.
+//!
+//! ```rust
+//! #[macro_use]
+//!
+//! use test_kind::test_kind;
+//!
+//! #[test_kind(e2e, resources=db, resources@staging=db, resources@prod=db-replica)]
+//! fn my_test() {
+//!    // Test code
+//! }
+//! ```
+//!
+//! ## Multiple Kinds
+//!
+//! A test can be more than one kind, by listing each of its kinds before the options.
+//! Each kind takes the options which apply to it, so `unit` still requires `updated`
+//! and every other kind (except `integration`) still requires `resources`.
+//!
+//! A test of multiple kinds:
+//!
+//! * Runs if any of its kinds would run.
+//! * Is skipped if none of its kinds would run, but at least one would be skipped.
+//!   The reason lists why each of the skipped kinds was skipped.
+//! * Is ignored only if every one of its kinds would be ignored.
+//!
+//! So an aged out `unit` test which is also an `integration` test still runs, unless
+//! integration tests are excluded, in which case it is skipped.
+//! If the unit test is old enough to be silently ignored, the test is still skipped
+//! because it is excluded as an integration test.
+//!
+//! These are specified as:
+//! ```rust
+//! #[macro_use]
+//!
+//! use test_kind::test_kind;
+//!
+//! #[test_kind(unit, integration, updated=2023-10-15)]
+//! fn my_test() {
+//!    // Test code
+//! }
+//! ```
+//!
+//! ## Options
+//!
+//! The kinds of a test come first, followed by its `key=value` options in any order.
+//! Each option may only be given once, except for `resources`, which are accumulated, so
+//! `resources=db, resources=redis` is the same as `resources=db, redis`.
+//! A resource may still only be given once across all of them.
+//!
+//! | Kind          | Required    | Optional                         |
+//! |---------------|-------------|----------------------------------|
+//! | `unit`        | `updated`   | `created`, `age`, `skip`         |
+//! | `integration` |             | `updated`                        |
+//! | `bench`       |             |                                  |
+//! | `manual`      |             |                                  |
+//! | anything else | `resources` | `updated`, `resources@<profile>` |
+//!
+//! Every kind may also have any of `expires`, `os`, `arch`, `features`, `priority`, `reason`,
+//! `owners`, `since`, `harness`, `harness_wasm`, `known_resources`, `flaky`, `timeout` and
+//! `quarantine`, which are described below.
+//! Any other option, such as a mistyped `updatd`, is a compile error which lists the options
+//! the kind takes, rather than being ignored.
+//!
+//! Tools which rewrite tests can parse the kinds and their options with `AttributeKind`,
+//! which displays them in a canonical form, with the options in the order of the table,
+//! that parses back to the same kind:
+//!
+//! ```rust
+//! use test_kind::AttributeKind;
+//!
+//! let kind: AttributeKind = "e2e, updated=2024-01-01, resources=db redis".parse().unwrap();
+//! assert_eq!(kind.to_string(), r#"e2e, resources="db, redis", updated=2024-01-01"#);
+//! ```
+//!
+//! Kinds with `resources@<profile>` lists are not parsed, as only the resources of the active
+//! profile are kept.
+//!
+//! ## Expiry
+//!
+//! Any kind of test can be given a hard deadline with the optional `expires` parameter.
+//! Unlike unit test age-out, which skips and then silences old tests, a test which has
+//! expired fails to compile, naming the test and when it expired.
+//! The test expires once the current date is after the expiry date.
+//!
+//! These are specified as:
+//! ```rust
+//! #[macro_use]
+//!
+//! use test_kind::test_kind;
+//!
+//! #[test_kind(integration, expires="2100-01-01")]
+//! fn my_test() {
+//!    // Test code
+//! }
+//! ```
+//!
+//! ## Platforms
+//!
+//! Any kind of test can be limited to particular platforms with the optional `os`
+//! and `arch` parameters, which list the operating systems and CPU architectures the
+//! test runs on. These are the names given by `std::env::consts::OS` and
+//! `std::env::consts::ARCH`, and unknown names are rejected.
+//! A test which would otherwise run is skipped on any other platform.
+//!
+//! The platform is the one compiling the tests, so these should not be used when
+//! cross compiling.
+//!
+//! These are specified as:
+//! ```rust
+//! #[macro_use]
+//!
+//! use test_kind::test_kind;
+//!
+//! #[test_kind(end2end, resources=db, os="linux,macos", arch="x86_64,aarch64")]
+//! fn my_test() {
+//!    // Test code
+//! }
+//! ```
+//!
+//! ## Features
+//!
+//! Any kind of test can require cargo features of the crate it is in, with the optional
+//! `features` parameter. The test is only compiled when all of the features are enabled,
+//! as if it was also marked `#[cfg(all(feature = "foo", feature = "bar"))]`.
+//!
+//! This applies whatever else is decided about the test. When the features are enabled,
+//! the test runs, is skipped or is ignored as usual. When they are not, the test is not
+//! compiled at all, so it does not show as skipped. Tests which fail to build, such as
+//! expired tests, still fail whether the features are enabled or not.
+//!
+//! These are specified as:
+//! ```rust
+//! #[macro_use]
+//!
+//! use test_kind::test_kind;
+//!
+//! #[test_kind(integration, features="foo,bar")]
+//! fn my_test() {
+//!    // Test code
+//! }
+//! ```
+//!
+//! ## Default Kind
+//!
+//! Every `#[test]` function in a module can be given a default kind, by applying
+//! the `test_kind_default` attribute to the module.
+//! It takes the same parameters as `test_kind`, and if none are given the parameters
+//! are read from the `TEST_KIND_DEFAULT` env var. If neither are set, it is an error.
+//!
+//! Only `#[test]` functions directly inside the module are affected.
+//! Functions with their own `#[test_kind]` attribute keep it, the explicit kind always wins.
+//! The module must be an inline module, and not in a separate file.
+//!
+//! `test_kind` itself also takes its parameters from `TEST_KIND_DEFAULT` when it is given none,
+//! so `#[test_kind]` is a test of the default kind. If it is not set, it is an error which
+//! describes the parameters it takes.
+//!
+//! These are specified as:
+//! ```rust,ignore
+//! #[macro_use]
+//!
+//! use test_kind::test_kind_default;
+//!
+//! #[test_kind_default(integration)]
+//! mod my_tests {
+//!     use test_kind::test_kind;
+//!
+//!     #[test]
+//!     fn my_test() {
+//!        // Test code
+//!     }
+//!
+//!     #[test_kind(end2end, resources=db)]
+//!     fn my_other_test() {
+//!        // Test code
+//!     }
+//! }
+//! ```
+//!
+//! ## Skip Reason
+//!
+//! Any kind of test can be given a custom `reason`, such as to explain why it is quarantined.
+//! It is only used if the test is skipped, when it is given before the automatic reason.
+//!
+//! These are specified as:
+//! ```rust
+//! #[macro_use]
+//!
+//! use test_kind::test_kind;
+//!
+//! #[test_kind(end2end, resources=db, reason="flaky under load, see #1234")]
+//! fn my_test() {
+//!    // Test code
+//! }
+//! ```
+//!
+//! Every skip reason starts with a token saying why the test was skipped, so tooling can
+//! parse it, followed by the test's name and the reason for people. For example:
+//! `[test_kind:missing_resources] my_test: flaky under load, see #1234 (Test of kind: ...)`.
+//!
+//! | Token | Skipped because |
+//! |-------|-----------------|
+//! | `[test_kind:excluded]` | Its kind is excluded by `TEST_KIND_EXCLUDE`, or `TEST_KIND_EXCLUDE_ALL_OTHER` |
+//! | `[test_kind:not_included]` | Its kind is not in `TEST_KIND_INCLUDE` |
+//! | `[test_kind:excluded_resources]` | A resource it requires is in `TEST_KIND_EXCLUDE_RESOURCE` |
+//! | `[test_kind:missing_resources]` | A resource it requires is not available |
+//! | `[test_kind:forbidden_resources]` | A resource it forbids is available |
+//! | `[test_kind:aged]` | It has aged out |
+//! | `[test_kind:platform]` | It can not run on this `os` or `arch` |
+//! | `[test_kind:priority]` | Its priority is below `TEST_KIND_MIN_PRIORITY` |
+//! | `[test_kind:sampled]` | It is not in the sample of `TEST_KIND_SAMPLE_PERCENT` |
+//! | `[test_kind:ignored]` | It has its own `#[ignore]`, only in reports and logs |
+//! | `[test_kind:dry_run]` | `TEST_KIND_DRY_RUN` is set |
+//! | `[test_kind:manual]` | It is a manual test, and `TEST_KIND_RUN_MANUAL` is not set |
+//! | `[test_kind:multiple]` | Its kinds were skipped for different reasons, each given with its token |
+//! | `[test_kind:runtime]` | It skipped itself with `skip_if!`, only on stderr and in reports |
+//!
+//! ## Owners
+//!
+//! Any kind of test can name its `owners`, as a comma separated list of people or teams,
+//! so CI can tell them when it is skipped. They are added to the end of the reason the test
+//! is skipped, and recorded in `TEST_KIND_REPORT`. They never change whether it runs.
+//!
+//! These are specified as:
+//! ```rust
+//! #[macro_use]
+//!
+//! use test_kind::test_kind;
+//!
+//! #[test_kind(end2end, resources=db, owners="team-data,alice")]
+//! fn my_test() {
+//!    // Test code
+//! }
+//! ```
+//!
+//! ## Since
+//!
+//! Any kind of test can record the version of the crate it was added in, with the optional
+//! `since` parameter, which must be a semantic version such as `1.4.0`.
+//! It does not change whether the test runs, but is recorded in the `TEST_KIND_REPORT`,
+//! so tests added since a release can be listed.
+//!
+//! These are specified as:
+//! ```rust
+//! #[macro_use]
+//!
+//! use test_kind::test_kind;
+//!
+//! #[test_kind(integration, since="1.4.0")]
+//! fn my_test() {
+//!    // Test code
+//! }
+//! ```
+//!
+//! ## Quarantine
+//!
+//! Any kind of test can be quarantined with the `quarantine` flag, to track a flaky test
+//! without it failing CI. A quarantined test still runs, but if it panics the panic is
+//! caught and reported on stderr, and the test passes.
+//! Setting the `TEST_KIND_QUARANTINE` env var to `enforce` makes quarantined tests fail
+//! like any other test. It is `report` by default, anything else fails the build.
+//!
+//! Only tests which are not async and return `()` or a `Result` can be quarantined.
+//! An `Err` returned by a quarantined test is reported like a panic, and the test passes.
+//! Tests with `#[should_panic]` can not be quarantined, as their panic is how they pass,
+//! so catching it would make them fail instead.
+//! Because `quarantine` is a flag, it can not also be used as the name of a kind or resource.
+//!
+//! These are specified as:
+//! ```rust
+//! #[macro_use]
+//!
+//! use test_kind::test_kind;
+//!
+//! #[test_kind(integration, quarantine)]
+//! fn my_test() {
+//!    // Test code
+//! }
+//! ```
+//!
+//! ## Flaky
+//!
+//! A test which is genuinely flaky can be retried with `flaky`, which gives how many times
+//! it is retried after it fails. The test passes on its first success, and fails if its last
+//! attempt fails. Each failed attempt which is retried is reported on stderr.
+//! Setting the `TEST_KIND_NO_RETRY` env var to `1` runs flaky tests only once.
+//!
+//! Only tests which are not async and return `()` or a `Result` can be retried.
+//! Returning an `Err` counts as a failed attempt, and the last attempt's `Err` is returned.
+//! Tests with `#[should_panic]` are never retried, as their panic is how they pass.
+//! A flaky test can also be quarantined, in which case only its last failure is reported.
+//!
+//! These are specified as:
+//! ```rust
+//! #[macro_use]
+//!
+//! use test_kind::test_kind;
+//!
+//! #[test_kind(integration, flaky=3)]
+//! fn my_test() {
+//!    // Test code
+//! }
+//! ```
+//!
+//! ## Timeout
+//!
+//! A test which may hang can be given a `timeout`, as a whole number of milliseconds (`500ms`),
+//! seconds (`30s`) or minutes (`2m`). The test then runs on its own thread, and fails with a
+//! panic saying it timed out if it runs for longer. Panics in the test are passed on unchanged.
+//! A flaky test has a timeout for each attempt.
+//! Setting the `TEST_KIND_NO_TIMEOUT` env var to `1` runs tests without their timeouts, such as
+//! when debugging them.
+//!
+//! Rust can not kill a thread, so a test which times out is left running in the background until
+//! every test has finished. It may still use resources, or interfere with the tests which follow.
+//! Only tests which are not async, take no arguments and return `()` or a `Result` can have a
+//! timeout. The result of a test which finishes in time is returned unchanged.
+//!
+//! These are specified as:
+//! ```rust
+//! #[macro_use]
+//!
+//! use test_kind::test_kind;
+//!
+//! #[test_kind(integration, timeout="30s")]
+//! fn my_test() {
+//!    // Test code
+//! }
+//! ```
+//!
+//! A test which should panic can not have a timeout, as timing out would pass it, so this is a
+//! compile error:
+//!
+//! ```rust,compile_fail
+//! use test_kind::test_kind;
+//!
+//! #[test_kind(integration, timeout="50ms")]
+//! #[should_panic]
+//! fn my_test() {
+//!     loop {}
+//! }
+//! ```
+//!
+//! ## Priority
+//!
+//! Any kind of test can be given a `priority`, a whole number where higher is more important,
+//! so that quick runs only run the most important tests.
+//! Tests without a priority have the priority in the `TEST_KIND_DEFAULT_PRIORITY` env var,
+//! or 0 if it is not set.
+//! When the `TEST_KIND_MIN_PRIORITY` env var is set, a test which would otherwise run is
+//! skipped if its priority is below it.
+//! If either is set to something which is not a whole number, the build fails.
+//!
+//! These are specified as:
+//! ```rust
+//! #[macro_use]
+//!
+//! use test_kind::test_kind;
+//!
+//! #[test_kind(integration, priority=1)]
+//! fn my_test() {
+//!    // Test code
+//! }
+//! ```
+//!
+//! ## Custom Test Harnesses
+//!
+//! Test harnesses which do not use `#[test]` can make the same decision as the `test_kind`
+//! macro with `decision_for`, which reads the `TEST_KIND_*` env vars when the tests run.
+//! Any warnings, such as for a unit test which is aging, are added to the list it is given.
+//!
+//! ```rust
+//! use test_kind::{decision_for, TestSettings};
+//!
+//! let mut warnings = Vec::new();
+//! match decision_for("end2end", None, &["db"], &mut warnings) {
+//!     Ok(TestSettings::Run) => { /* Run the test */ }
+//!     Ok(TestSettings::Skip { reason }) => println!("Skipped: {reason}"),
+//!     Ok(_) => { /* Ignore the test */ }
+//!     Err(errors) => panic!("Invalid test: {errors:?}"),
+//! }
+//! for warning in warnings {
+//!     println!("Warning: {warning}");
+//! }
+//! ```
+//!
+//! Each error is a `TestKindError`, which can be matched on, such as to report an
+//! `UndefinedKind` differently from an `UnknownResources`.
+//!
+//! The configuration `decision_for` uses can be printed with `dump_config`, to understand
+//! why tests are run or skipped.
+//!
+//! ```rust
+//! println!("{}", test_kind::dump_config());
+//! ```
+//!
+//! ## Resources at Runtime
+//!
+//! A test can check for a resource itself with `is_resource_available`, such as to choose which
+//! connection string to use. It reads `TEST_KIND_RESOURCES` when it is called, with resource
+//! groups expanded, and takes versions and alternatives like `resources`.
+//!
+//! ```rust
+//! let url = if test_kind::is_resource_available("redis") {
+//!     "redis://localhost"
+//! } else {
+//!     "memory://"
+//! };
+//! ```
+//!
+//! ## Skipping at Runtime
+//!
+//! Some tests can only tell whether they can run while they are running, such as by probing for
+//! a feature. The `skip_if!` macro skips the rest of a test when its condition is true, printing
+//! the reason on stderr as `[test_kind:runtime] <test>: <reason>` and returning, so the test
+//! passes. It can be used in tests which return `()` or a `Result`.
+//! When `TEST_KIND_REPORT` is set when the test runs, the reason is also appended to it, as a
+//! `skip` whose `kind` is `null`, after the line written when the test was compiled.
+//! It is not counted by `TEST_KIND_SUMMARY`, which only counts what was decided at compile time.
+//!
+//! The standard test harness can not skip a test once it has started, so it shows the test as
+//! passed, not ignored, unless it is combined with a harness which can skip tests.
+//!
+//! ```rust
+//! # use test_kind::test_kind;
+//! #[test_kind(integration)]
+//! fn my_test() {
+//!     test_kind::skip_if!(!std::path::Path::new("/dev/kvm").exists(), "no KVM");
+//!     // Test code which needs KVM
+//! }
+//! ```
+//!
+//! ## Test Harness
+//!
+//! By default every test is emitted with the standard `#[test]` attribute.
+//! Tests which need a different harness, such as async tests, can name the
+//! attribute to use instead with the optional `harness` parameter.
+//! It applies to any kind of test, and is also used when the test is skipped.
+//!
+//! Any other attributes of the test, such as `#[should_panic]` or `#[cfg]`, are kept
+//! after the harness attribute. If a skipped test has its own `#[ignore]`, it is replaced
+//! by the reason the test was skipped. A test which would run keeps its own `#[ignore]`,
+//! and is reported and logged as skipped, with its own reason.
+//!
+//! These are specified as:
+//! ```rust,ignore
+//! #[macro_use]
+//!
+//! use test_kind::test_kind;
+//!
+//! #[test_kind(integration, harness="tokio::test")]
+//! async fn my_test() {
+//!    // Test code
+//! }
+//! ```
+//!
+//! Crates which are also tested on `wasm32` can name the harness to use there with the
+//! optional `harness_wasm` parameter. The test then has the `harness_wasm` attribute when
+//! compiled for `wasm32`, and its `harness`, or `#[test]`, otherwise, each with `#[cfg_attr]`.
+//! For `wasm_bindgen_test` the crate must have it as a dev-dependency, which is only
+//! needed when testing on `wasm32`:
+//!
+//! ```toml
+//! [target.'cfg(target_arch = "wasm32")'.dev-dependencies]
+//! wasm-bindgen-test = "0.3"
+//! ```
+//!
+//! ```rust,ignore
+//! #[macro_use]
+//!
+//! use test_kind::test_kind;
+//!
+//! #[test_kind(integration, harness_wasm="wasm_bindgen_test::wasm_bindgen_test")]
+//! fn my_test() {
+//!    // Test code
+//! }
+//! ```
+//!
+//! Like `#[test]`, `test_kind` can only be applied to free functions. Applying it to a
+//! method in an `impl` block, or to anything other than a function, is a compile error
+//! which says so.
+//!
+//! ```rust,compile_fail
+//! use test_kind::test_kind;
+//!
+//! struct Fixture;
+//!
+//! impl Fixture {
+//!     #[test_kind(integration)]
+//!     fn my_test(&self) {
+//!        // Test code
+//!     }
+//! }
+//! ```
+//!
+//! ```rust,compile_fail
+//! use test_kind::test_kind;
+//!
+//! #[test_kind(integration)]
+//! struct NotATest;
+//! ```
+//!
+//! ## Summary
+//!
+//! Custom test binaries can print a table of how many tests of each kind passed, failed,
+//! were skipped or were ignored. This is opt-in, with the `summary` feature, which adds
+//! a dependency on the `inventory` crate. With it every test registers its kind and what
+//! was decided to do with it, when it is compiled.
+//!
+//! The test binary is declared with `harness = false`, and generates its `main` with
+//! `test_kind_main!`, which runs the tests in it and then prints the summary:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! test_kind = { version = "0.1", features = ["summary"] }
+//!
+//! [[test]]
+//! name = "e2e"
+//! harness = false
+//! ```
+//!
+//! ```rust,ignore
+//! use test_kind::test_kind;
+//!
+//! test_kind::test_kind_main!();
+//!
+//! #[test_kind(e2e, resources=db)]
+//! fn my_test() {
+//!    // Test code
+//! }
+//! ```
+//!
+//! Only tests using the standard `#[test]` harness, which take no arguments and return `()`,
+//! can be run by `test_kind_main!`. Other tests which would run are counted as skipped.
+//! The tests are run one after the other, and the binary fails if any of them fail.
+//! It does not take the arguments of the standard harness, such as a filter.
+//!
+//! ### Unused Kinds
+//!
+//! A kind in `TEST_KIND_DEFINED` which no test uses is often a mistyped kind.
+//! With the `summary` feature, `assert_kinds_used!()` adds a test which fails, listing every
+//! defined kind which is not the kind of a registered test:
+//!
+//! ```rust,ignore
+//! test_kind::assert_kinds_used!();
+//! ```
+//!
+//! Tests are registered when they are compiled, and gathered when the test binary runs, so
+//! only the tests in the same binary are seen. Each file in `tests/` is its own binary, as
+//! are the unit tests of each crate, so it belongs in a binary which includes all the tests,
+//! such as one integration test whose other tests are its modules.
+//! Tests which fail to compile, or whose features are not enabled, are not registered.
+//! It is a standard `#[test]`, so it is not run by `test_kind_main!`.
+//! When `TEST_KIND_DEFINED` is not set, every kind is defined, and it always passes.
+//!
+//! ### Kinds
+//!
+//! With the `summary` feature, `kinds()` lists every distinct kind of the registered tests,
+//! such as for a coverage dashboard, or to compare with `TEST_KIND_DEFINED`. A test of multiple
+//! kinds lists each of them. Like `assert_kinds_used!()`, it only lists the kinds of the tests
+//! which are compiled into the same binary, whether or not they are run.
+//!
+//! ```rust,ignore
+//! for kind in test_kind::kinds() {
+//!     println!("{kind}");
+//! }
+//! ```
+pub use test_kind_core::report::read_summary_totals;
+#[doc(hidden)]
+pub use test_kind_core::report::report_runtime_skip;
+pub use test_kind_core::{
+    decision_for, dump_config, is_known_resource, is_resource_available, AttributeKind, Config,
+    SkipCategory, SkipReason, Skipped, TestKindError, TestSettings,
+};
+pub use test_kind_macros::{test_kind, test_kind_default};
 
-use attribute_kind::{AttributeKind, TestSettings};
+/// Add a test which fails if any kind in `TEST_KIND_DEFINED` is not the kind of any test.
+///
+/// Only available with the `summary` feature, see the crate documentation.
+#[cfg(feature = "summary")]
+pub use test_kind_macros::assert_kinds_used;
 
-use proc_macro::TokenStream;
-use quote::quote;
-use syn::parse_macro_input;
+#[cfg(feature = "summary")]
+pub mod summary;
 
-#[proc_macro_attribute]
-pub fn test_kind(attr: TokenStream, input: TokenStream) -> TokenStream {
-    // Parse the input tokens into a syntax tree
-    let test_fn = parse_macro_input!(input as syn::ItemFn);
+#[cfg(feature = "summary")]
+pub use summary::kinds;
 
-    // Parse the attribute arguments
-    let attr_str = attr.to_string();
-    let kind = match AttributeKind::from_str(&attr_str) {
-        Ok(kind) => kind,
-        Err(err) => return err.to_compile_error().into(),
+/// Register the known resources of the tests in the code, as a constant.
+///
+/// Tests name the constant with their `known_resources` parameter, and fail to compile
+/// if they use a resource which is not in it, or in `TEST_KIND_KNOWN_RESOURCES`.
+///
+/// ```rust
+/// test_kind::resources!(pub KNOWN_RESOURCES = ["db", "redis"]);
+///
+/// assert!(test_kind::is_known_resource(KNOWN_RESOURCES, "db", true));
+/// ```
+#[macro_export]
+macro_rules! resources {
+    ($vis:vis $name:ident = [$($resource:literal),* $(,)?]) => {
+        $vis const $name: &[&str] = &[$($resource),*];
     };
+}
 
-    match kind.what_to_do() {
-        TestSettings::Run => {
-            eprintln!("Run");
-            // Return the test function, and allow it to run.
-            quote! {
-                #[test]
-                #test_fn
-            }
+/// Skip the rest of a test while it is running, when the `condition` is true.
+///
+/// The `reason` is printed, and reported to `TEST_KIND_REPORT`, then the test returns so it
+/// passes, whether it returns `()` or a `Result`. See [Skipping at Runtime](crate#skipping-at-runtime).
+///
+/// ```rust
+/// fn my_test() -> Result<(), String> {
+///     test_kind::skip_if!(std::env::var("GPU").is_err(), "no GPU");
+///     Err("needs a GPU".to_owned())
+/// }
+/// assert_eq!(my_test(), Ok(()));
+/// ```
+#[macro_export]
+macro_rules! skip_if {
+    ($condition:expr, $reason:expr $(,)?) => {
+        if $condition {
+            $crate::report_runtime_skip(::std::convert::AsRef::<str>::as_ref(&$reason));
+            return $crate::Skipped::skipped();
         }
-        TestSettings::Ignore => {
-            eprintln!("Ignore");
-            // Return an empty TokenStream to exclude the function from the code
-            quote!()
-        }
-        TestSettings::Skip { reason } => {
-            eprintln!("Skip {reason}");
-            quote! {
-               #[test]
-               #[ignore = #reason]
-               #test_fn
-            }
+    };
+}
+
+/// Generate the `main` of a custom test binary, which runs its tests and summarizes them by kind.
+///
+/// Only available with the `summary` feature, see the crate documentation.
+#[cfg(feature = "summary")]
+#[macro_export]
+macro_rules! test_kind_main {
+    () => {
+        fn main() -> ::std::process::ExitCode {
+            $crate::summary::run()
         }
-    }
-    .into()
+    };
 }
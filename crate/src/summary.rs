@@ -0,0 +1,110 @@
+//! Registry of the tests, so custom test binaries can summarize what was done with them.
+//!
+//! Only available with the `summary` feature, see `test_kind_main!`.
+
+use std::panic;
+use std::process::ExitCode;
+
+use test_kind_core::summary::{distinct_kinds, summary_table, unused_kinds, Outcome};
+
+#[doc(hidden)]
+pub use inventory;
+
+/// A test, as registered by the `test_kind` macro.
+#[doc(hidden)]
+pub struct TestRecord {
+    /// Full path of the test function.
+    pub name: &'static str,
+    /// The name of the kind of test, multiple kinds are comma separated.
+    pub kind: &'static str,
+    /// What was decided to do with the test: `run`, `ignore` or `skip`.
+    pub decision: &'static str,
+    /// The test function, if it is run and can be run without its own harness.
+    pub run: Option<fn()>,
+    /// Does the test pass when it panics?
+    pub should_panic: bool,
+}
+
+inventory::collect!(TestRecord);
+
+/// Run a single test, catching any panic.
+fn run_test(test: fn(), should_panic: bool) -> Outcome {
+    if panic::catch_unwind(test).is_err() == should_panic {
+        Outcome::Passed
+    } else {
+        Outcome::Failed
+    }
+}
+
+/// Panic if any of the `defined` kinds is not the kind of a registered test.
+///
+/// This is the test generated by `assert_kinds_used!`, which gives it the kinds in
+/// `TEST_KIND_DEFINED` when it was compiled.
+#[doc(hidden)]
+pub fn assert_kinds_used(defined: &[&str], case_sensitive: bool) {
+    let used = inventory::iter::<TestRecord>
+        .into_iter()
+        .map(|record| record.kind);
+    let unused = unused_kinds(defined, used, case_sensitive);
+    assert!(
+        unused.is_empty(),
+        "Kinds in `TEST_KIND_DEFINED` which no test uses: {unused:?}"
+    );
+}
+
+/// Every distinct kind of the registered tests, in order of their names.
+///
+/// Only the tests compiled into the same binary are registered, see the crate documentation.
+#[must_use]
+pub fn kinds() -> Vec<String> {
+    distinct_kinds(
+        inventory::iter::<TestRecord>
+            .into_iter()
+            .map(|record| record.kind),
+    )
+}
+
+/// Run every registered test which is to be run, and print a summary of them by kind.
+///
+/// Tests which need their own harness, such as async tests, are not run and count as skipped.
+///
+/// Returns a failure if any test failed.
+#[must_use]
+pub fn run() -> ExitCode {
+    let mut records: Vec<&TestRecord> = inventory::iter::<TestRecord>.into_iter().collect();
+    records.sort_by_key(|record| record.name);
+
+    let mut outcomes = Vec::new();
+    for record in records {
+        let outcome = match (record.decision, record.run) {
+            ("run", Some(test)) => run_test(test, record.should_panic),
+            ("run", None) => {
+                println!("test {} ... skipped, it needs its own harness", record.name);
+                outcomes.push((record.kind, Outcome::Skipped));
+                continue;
+            }
+            ("ignore", _) => Outcome::Ignored,
+            _ => Outcome::Skipped,
+        };
+        let result = match outcome {
+            Outcome::Passed => "ok",
+            Outcome::Failed => "FAILED",
+            Outcome::Skipped => "skipped",
+            Outcome::Ignored => "ignored",
+        };
+        println!("test {} ... {result}", record.name);
+        outcomes.push((record.kind, outcome));
+    }
+
+    let failed = outcomes
+        .iter()
+        .any(|(_, outcome)| *outcome == Outcome::Failed);
+    println!();
+    print!("{}", summary_table(outcomes));
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
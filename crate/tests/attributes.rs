@@ -0,0 +1,62 @@
+use test_kind::test_kind;
+
+#[test_kind(integration)]
+#[should_panic(expected = "x")]
+fn run_test_should_panic() {
+    panic!("x");
+}
+
+#[should_panic(expected = "x")]
+#[test_kind(integration)]
+fn run_test_should_panic_before_test_kind() {
+    panic!("x");
+}
+
+#[test_kind(integration, os = "redox")]
+#[should_panic(expected = "x")]
+fn skipped_test_should_panic() {
+    panic!("x");
+}
+
+#[test_kind(integration)]
+#[cfg(any())]
+fn run_test_configured_out() {
+    compile_error!("Configured out tests are not compiled");
+}
+
+#[test_kind(integration, os = "redox")]
+#[cfg(any())]
+fn skipped_test_configured_out() {
+    compile_error!("Configured out tests are not compiled");
+}
+
+#[test_kind(integration)]
+#[cfg_attr(all(), should_panic(expected = "x"))]
+fn run_test_with_cfg_attr() {
+    panic!("x");
+}
+
+/// Doc comments are kept.
+#[test_kind(integration)]
+#[allow(clippy::unwrap_used)]
+fn run_test_with_docs() {
+    // Test code
+}
+
+#[test_kind(integration, os = "redox")]
+#[ignore = "Replaced by the skip reason"]
+fn skipped_test_already_ignored() {
+    // Test code
+}
+
+#[test_kind(integration)]
+#[ignore = "Still ignored when it runs"]
+fn run_test_already_ignored() {
+    panic!("Tests with their own `#[ignore]` are not run");
+}
+
+#[test_kind(integration)]
+#[ignore]
+fn run_test_already_ignored_without_a_reason() {
+    panic!("Tests with their own `#[ignore]` are not run");
+}
@@ -0,0 +1,137 @@
+use test_kind::{decision_for, TestKindError, TestSettings};
+
+/// The errors of an invalid test, or none if it is valid.
+fn errors(decision: Result<TestSettings, Vec<TestKindError>>) -> Vec<TestKindError> {
+    decision.err().unwrap_or_default()
+}
+
+#[test]
+fn integration_tests_run() {
+    assert_eq!(
+        decision_for("integration", None, &[], &mut Vec::new()),
+        Ok(TestSettings::Run)
+    );
+}
+
+#[test]
+fn old_unit_tests_are_ignored() {
+    assert_eq!(
+        decision_for("unit", Some("2023-10-10"), &[], &mut Vec::new()),
+        Ok(TestSettings::Ignore)
+    );
+}
+
+#[test]
+fn other_tests_require_resources() {
+    assert!(matches!(
+        decision_for(
+            "e2e",
+            None,
+            &["test_kind_unavailable_resource"],
+            &mut Vec::new()
+        ),
+        Ok(TestSettings::Skip { .. })
+    ));
+}
+
+#[test]
+fn resources_can_have_an_exact_version_anywhere() {
+    let settings = decision_for(
+        "e2e",
+        None,
+        &["test_kind_unavailable_resource", "postgres=16"],
+        &mut Vec::new(),
+    );
+    assert!(matches!(
+        settings,
+        Ok(TestSettings::Skip { ref reason }) if reason.text.contains("postgres=16")
+    ));
+}
+
+#[test]
+fn invalid_tests_are_errors() {
+    assert_eq!(
+        decision_for("unit", None, &[], &mut Vec::new()),
+        Err(vec![TestKindError::InvalidAttributes(
+            "Test kind 'unit' requires `updated=YYYY-MM-DD`".to_owned()
+        )])
+    );
+    assert!(matches!(
+        errors(decision_for("e2e", None, &[], &mut Vec::new())).as_slice(),
+        [TestKindError::InvalidAttributes(_)]
+    ));
+    assert_eq!(
+        decision_for("integration", None, &["db"], &mut Vec::new()),
+        Err(vec![TestKindError::InvalidAttributes(
+            "Invalid options for test kind 'integration': [\"resources\"], it takes [\"updated\"]"
+                .to_owned()
+        )])
+    );
+    assert!(matches!(
+        errors(decision_for("e2e", None, &["db|"], &mut Vec::new())).as_slice(),
+        [TestKindError::InvalidResources(_)]
+    ));
+    assert!(matches!(
+        errors(decision_for(
+            "integration",
+            Some("2020-01-01"),
+            &[],
+            &mut Vec::new()
+        ))
+        .as_slice(),
+        [TestKindError::InvalidDate(_)]
+    ));
+}
+
+#[test]
+fn values_can_not_add_attributes() {
+    assert_eq!(
+        decision_for("e2e", None, &["db, updated=2024-01-01"], &mut Vec::new()),
+        Err(vec![TestKindError::InvalidResources(
+            "Invalid resource name `db, updated`, it may only contain letters, numbers, `_` and `-`"
+                .to_owned()
+        )])
+    );
+    assert!(matches!(
+        errors(decision_for(
+            "unit",
+            Some("2024-01-01\", age=\"9"),
+            &[],
+            &mut Vec::new()
+        ))
+        .as_slice(),
+        [TestKindError::InvalidDate(_)]
+    ));
+    assert!(matches!(
+        errors(decision_for(
+            "e2e, os=linux",
+            None,
+            &["db"],
+            &mut Vec::new()
+        ))
+        .as_slice(),
+        [TestKindError::InvalidAttributes(_)]
+    ));
+}
+
+#[test]
+fn every_error_is_returned() {
+    let errors =
+        decision_for("e2e", Some("yesterday"), &["db", "db"], &mut Vec::new()).unwrap_err();
+    assert!(matches!(
+        errors.as_slice(),
+        [
+            TestKindError::DuplicateResource,
+            TestKindError::InvalidDate(_)
+        ]
+    ));
+    assert!(errors[1].to_string().starts_with("Invalid date format"));
+}
+
+#[test]
+fn config_can_be_dumped() {
+    let config = test_kind::dump_config();
+    assert!(config
+        .to_string()
+        .contains(&format!("TEST_KIND_UNIT_AGE: {} days", config.unit_age)));
+}
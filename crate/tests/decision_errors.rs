@@ -0,0 +1,24 @@
+use std::env;
+
+use test_kind::{decision_for, TestKindError};
+
+// The env vars are read once, so they are set before anything else and this is the only test
+// in this file.
+#[test]
+fn undefined_kinds_and_unknown_resources_are_errors() {
+    env::set_var("TEST_KIND_DEFINED", "end2end");
+    env::set_var("TEST_KIND_KNOWN_RESOURCES", "db");
+    assert_eq!(
+        decision_for("smoke", None, &[], &mut Vec::new()).err(),
+        Some(vec![TestKindError::UndefinedKind {
+            kind: "smoke".to_owned()
+        }])
+    );
+    assert_eq!(
+        decision_for("end2end", None, &["db", "redis"], &mut Vec::new()).err(),
+        Some(vec![TestKindError::UnknownResources {
+            resources: vec!["redis".to_owned()]
+        }])
+    );
+    assert!(decision_for("end2end", None, &["db"], &mut Vec::new()).is_ok());
+}
@@ -0,0 +1,23 @@
+use std::env;
+
+use test_kind::{decision_for, TestSettings};
+
+// The env vars are read once, so they are set before anything else and this is the only test
+// in this file.
+#[test]
+fn aging_tests_return_warnings() {
+    env::set_var("TEST_KIND_UNIT_WARN", "30");
+    let mut warnings = Vec::new();
+    assert_eq!(
+        decision_for("unit", Some("100d"), &[], &mut warnings),
+        Ok(TestSettings::Run)
+    );
+    assert_eq!(warnings, ["Unit test is aging. Skipped in 265 days"]);
+
+    let mut warnings = Vec::new();
+    assert_eq!(
+        decision_for("unit", Some("7d"), &[], &mut warnings),
+        Ok(TestSettings::Run)
+    );
+    assert!(warnings.is_empty());
+}
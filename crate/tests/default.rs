@@ -0,0 +1,16 @@
+use test_kind::test_kind_default;
+
+#[test_kind_default(integration)]
+mod integration_by_default {
+    use test_kind::test_kind;
+
+    #[test]
+    fn default_integration_test() {
+        // Test code
+    }
+
+    #[test_kind(e2e, resources=db)]
+    fn explicit_e2e_test() {
+        // Test code
+    }
+}
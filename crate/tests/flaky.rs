@@ -0,0 +1,44 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use test_kind::test_kind;
+
+static ATTEMPTS: AtomicU32 = AtomicU32::new(0);
+
+#[test_kind(integration, flaky = 2)]
+fn flaky_test() {
+    // Passes on its last attempt, unless `TEST_KIND_NO_RETRY=1`.
+    if std::env::var("TEST_KIND_NO_RETRY").as_deref() != Ok("1") {
+        assert!(ATTEMPTS.fetch_add(1, Ordering::SeqCst) >= 2, "flaky");
+    }
+}
+
+#[test_kind(integration, flaky = 3)]
+#[should_panic(expected = "always")]
+fn flaky_test_which_should_panic() {
+    panic!("always");
+}
+
+// A harness which passes the test an argument, such as a fixture, is stood in for by `inline`,
+// so the test is called by another test.
+#[test_kind(integration, flaky = 2, harness = "inline")]
+fn flaky_test_with_argument(attempts: &mut u32) {
+    *attempts += 1;
+    if std::env::var("TEST_KIND_NO_RETRY").as_deref() != Ok("1") {
+        assert!(*attempts >= 2, "flaky");
+    }
+}
+
+#[test]
+fn flaky_tests_can_take_arguments() {
+    let mut attempts = 0;
+    flaky_test_with_argument(&mut attempts);
+    assert!(attempts >= 1);
+}
+
+#[test_kind(integration, flaky = 2, quarantine)]
+fn flaky_quarantined_test() {
+    // Reported rather than failing, after every retry.
+    if std::env::var("TEST_KIND_QUARANTINE").as_deref() != Ok("enforce") {
+        panic!("flaky");
+    }
+}
@@ -4,3 +4,38 @@ use test_kind::test_kind;
 fn integration_test() {
     // Test code
 }
+
+#[test_kind(integration, harness = "core::prelude::v1::test")]
+fn integration_test_with_harness() {
+    // Test code
+}
+
+#[test_kind(integration, updated = "2024-01-01")]
+fn integration_test_with_updated_date() {
+    // Only ages out when `TEST_KIND_INTEGRATION_AGE` is set.
+}
+
+#[test_kind(integration, expires = "2100-01-01")]
+fn integration_test_not_yet_expired() {
+    // Test code
+}
+
+#[test_kind(integration, os = "linux,macos,windows")]
+fn integration_test_on_common_os() {
+    // Test code
+}
+
+#[test_kind(integration, os = "redox", arch = "riscv64")]
+fn integration_test_on_other_platform() {
+    // Test code
+}
+
+#[test_kind(integration, os = "redox")]
+fn r#type() {
+    // Test code
+}
+
+#[test_kind(integration, priority = 1)]
+fn integration_test_with_priority() {
+    // Test code
+}
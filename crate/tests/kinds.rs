@@ -0,0 +1,24 @@
+use test_kind::test_kind;
+
+#[test_kind(integration)]
+fn integration_test() {}
+
+#[test_kind(e2e, api, resources = db)]
+fn e2e_and_api_test() {}
+
+#[test_kind(unit, updated = "2024-01-01")]
+fn unit_test() {}
+
+#[test_kind(load, resources = "cluster")]
+fn load_test() {}
+
+#[test_kind(e2e, resources = "db")]
+fn another_e2e_test() {}
+
+#[test]
+fn every_kind_is_listed_once() {
+    assert_eq!(
+        test_kind::kinds(),
+        ["api", "e2e", "integration", "load", "unit"]
+    );
+}
@@ -0,0 +1,20 @@
+use test_kind::test_kind;
+
+test_kind::assert_kinds_used!();
+
+#[test_kind(integration)]
+fn integration_test_runs() {}
+
+#[test_kind(e2e, api, resources = db)]
+fn e2e_and_api_test() {}
+
+#[test]
+fn registered_kinds_are_used() {
+    test_kind::summary::assert_kinds_used(&["integration", "e2e", "API"], false);
+}
+
+#[test]
+#[should_panic(expected = "Kinds in `TEST_KIND_DEFINED` which no test uses: [\"load\"]")]
+fn unused_kinds_fail() {
+    test_kind::summary::assert_kinds_used(&["e2e", "load"], false);
+}
@@ -0,0 +1,23 @@
+use test_kind::{is_known_resource, test_kind};
+
+test_kind::resources!(KNOWN_RESOURCES = ["db", "Redis"]);
+
+#[test_kind(e2e, resources=db, redis, known_resources=KNOWN_RESOURCES)]
+fn e2e_test_with_registered_resources() {
+    // Test code
+}
+
+#[test_kind(unit, e2e, updated=2023-10-10, resources=db|redis, known_resources=KNOWN_RESOURCES)]
+fn multi_test_with_registered_resources() {
+    // Test code
+}
+
+#[test]
+fn known_resources_are_matched() {
+    assert!(is_known_resource(KNOWN_RESOURCES, "db", true));
+    assert!(is_known_resource(KNOWN_RESOURCES, "redis", false));
+    assert!(!is_known_resource(KNOWN_RESOURCES, "redis", true));
+    assert!(!is_known_resource(KNOWN_RESOURCES, "d", false));
+    assert!(!is_known_resource(KNOWN_RESOURCES, "kafka", false));
+    assert!(!is_known_resource(&[], "db", false));
+}
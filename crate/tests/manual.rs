@@ -0,0 +1,6 @@
+use test_kind::test_kind;
+
+#[test_kind(manual)]
+fn manual_test() {
+    // Only run when `TEST_KIND_RUN_MANUAL=1`.
+}
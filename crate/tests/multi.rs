@@ -0,0 +1,11 @@
+use test_kind::test_kind;
+
+#[test_kind(unit, integration, updated=2023-10-10)]
+fn unit_and_integration_test() {
+    // Test code
+}
+
+#[test_kind(integration, e2e, resources=db)]
+fn integration_and_e2e_test() {
+    // Test code
+}
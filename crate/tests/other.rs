@@ -9,3 +9,38 @@ fn e2e_test() {
 fn api_test() {
     // Test code
 }
+
+#[test_kind(db, resources=postgres|mysql, redis)]
+fn db_test_with_alternatives() {
+    // Test code
+}
+
+#[test_kind(api, resources=db net)]
+fn api_test_with_space_separated_resources() {
+    // Test code
+}
+
+#[test_kind(api, resources = "db net, mock_server-2")]
+fn api_test_with_mixed_separators() {
+    // Test code
+}
+
+#[test_kind(api, resources=db, updated=2023-10-15)]
+fn api_test_with_updated_date() {
+    // Test code
+}
+
+#[test_kind(e2e, resources=db, reason="flaky under load, see #1234")]
+fn e2e_test_with_reason() {
+    // Test code
+}
+
+#[test_kind(e2e, resources=db, resources@staging=db-replica)]
+fn e2e_test_with_profile_resources() {
+    // Test code
+}
+
+#[test_kind(e2e, resources=!mock_server, db)]
+fn e2e_test_without_mock_server() {
+    // Test code
+}
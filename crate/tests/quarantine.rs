@@ -0,0 +1,19 @@
+use test_kind::test_kind;
+
+#[test_kind(integration, quarantine)]
+fn quarantined_test() {
+    // Test code
+}
+
+#[test_kind(integration, quarantine)]
+fn quarantined_failing_test() {
+    // Reported rather than failing, unless `TEST_KIND_QUARANTINE=enforce`.
+    if std::env::var("TEST_KIND_QUARANTINE").as_deref() != Ok("enforce") {
+        panic!("flaky");
+    }
+}
+
+#[test_kind(end2end, resources=db, quarantine)]
+fn quarantined_test_with_resources() {
+    // Test code
+}
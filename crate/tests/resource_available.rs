@@ -0,0 +1,20 @@
+use std::env;
+
+use test_kind::is_resource_available;
+
+// The env vars are only changed by this test, so it is the only test in this file.
+#[test]
+fn resources_are_checked_as_they_are_now() {
+    env::set_var("TEST_KIND_RESOURCES", "test_kind_redis,test_kind_stores");
+    env::set_var(
+        "TEST_KIND_RESOURCE_GROUPS",
+        "test_kind_stores=test_kind_postgres",
+    );
+    assert!(is_resource_available("test_kind_redis"));
+    assert!(is_resource_available("test_kind_postgres"));
+    assert!(!is_resource_available("test_kind_mysql"));
+
+    env::remove_var("TEST_KIND_RESOURCES");
+    assert!(!is_resource_available("test_kind_redis"));
+    assert!(!is_resource_available("test_kind_postgres"));
+}
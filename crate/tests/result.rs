@@ -0,0 +1,65 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use test_kind::test_kind;
+
+#[test_kind(unit, updated = "7d")]
+fn unit_test_returning_result() -> Result<(), String> {
+    Ok(())
+}
+
+#[test_kind(integration)]
+fn integration_test_using_question_mark() -> Result<(), std::num::ParseIntError> {
+    let number: u32 = "42".parse()?;
+    assert_eq!(number, 42);
+    Ok(())
+}
+
+static ATTEMPTS: AtomicU32 = AtomicU32::new(0);
+
+#[test_kind(integration, flaky = 2)]
+fn flaky_test_returning_result() -> Result<(), String> {
+    // Returns an error until its last attempt, unless `TEST_KIND_NO_RETRY=1`.
+    if std::env::var("TEST_KIND_NO_RETRY").as_deref() != Ok("1")
+        && ATTEMPTS.fetch_add(1, Ordering::SeqCst) < 2
+    {
+        return Err("flaky".to_owned());
+    }
+    Ok(())
+}
+
+#[test_kind(integration, quarantine)]
+fn quarantined_test_returning_an_error() -> Result<(), String> {
+    // Reported rather than failing, unless `TEST_KIND_QUARANTINE=enforce`.
+    if std::env::var("TEST_KIND_QUARANTINE").as_deref() != Ok("enforce") {
+        return Err("flaky".to_owned());
+    }
+    Ok(())
+}
+
+#[test_kind(integration, timeout = "10s")]
+fn timed_test_returning_result() -> Result<(), String> {
+    Ok(())
+}
+
+// These fail, so they are only run by the tests below, which check their errors are kept.
+#[test_kind(integration, timeout = "10s")]
+#[ignore = "fails, run by errors_are_kept_by_timeouts"]
+fn timed_test_returning_an_error() -> Result<(), String> {
+    Err("failed".to_owned())
+}
+
+#[test_kind(integration, flaky = 2, timeout = "10s")]
+#[ignore = "fails, run by errors_are_kept_by_retries"]
+fn flaky_test_returning_an_error() -> Result<(), String> {
+    Err("always".to_owned())
+}
+
+#[test]
+fn errors_are_kept_by_timeouts() {
+    assert_eq!(timed_test_returning_an_error(), Err("failed".to_owned()));
+}
+
+#[test]
+fn errors_are_kept_by_retries() {
+    assert_eq!(flaky_test_returning_an_error(), Err("always".to_owned()));
+}
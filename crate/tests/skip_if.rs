@@ -0,0 +1,24 @@
+use test_kind::{skip_if, test_kind};
+
+#[test_kind(integration)]
+fn skipped_test_returns_early() {
+    skip_if!(true, "always skipped");
+    panic!("the test was not skipped");
+}
+
+#[test_kind(integration)]
+fn skipped_result_test_passes() -> Result<(), String> {
+    skip_if!(1 + 1 == 2, format!("skipped by {}", "a probe"));
+    Err("the test was not skipped".to_owned())
+}
+
+fn probe(skip: bool) -> Result<(), String> {
+    skip_if!(skip, "probe failed");
+    Err("ran".to_owned())
+}
+
+#[test_kind(integration)]
+fn tests_only_skip_when_asked() {
+    assert_eq!(probe(true), Ok(()));
+    assert_eq!(probe(false), Err("ran".to_owned()));
+}
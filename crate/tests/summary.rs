@@ -0,0 +1,33 @@
+use test_kind::test_kind;
+
+test_kind::test_kind_main!();
+
+#[test_kind(integration)]
+fn integration_test_runs() {
+    assert!(test_kind::is_known_resource(&["db"], "db", true));
+}
+
+#[test_kind(integration)]
+#[should_panic(expected = "x")]
+fn integration_test_should_panic() {
+    panic!("x");
+}
+
+#[test_kind(unit, updated = "3d")]
+fn unit_test_runs() {}
+
+#[test_kind(e2e, resources = test_kind_unavailable_resource)]
+fn missing_resources_are_skipped() {
+    unreachable!("skipped tests are not run");
+}
+
+#[test_kind(integration, os = "redox")]
+fn other_platforms_are_skipped() {
+    unreachable!("skipped tests are not run");
+}
+
+#[test_kind(integration)]
+#[cfg(any())]
+fn configured_out_tests_are_not_registered() {
+    compile_error!("Configured out tests are not compiled");
+}
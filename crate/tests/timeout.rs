@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use test_kind::test_kind;
+
+#[test_kind(integration, timeout = "10s")]
+fn finishes_in_time() {
+    std::thread::sleep(Duration::from_millis(10));
+}
+
+// These fail, so they are only run by the tests below, which check how they failed.
+#[test_kind(integration, timeout = "50ms")]
+#[ignore = "times out, run by hanging_tests_time_out"]
+fn hangs() {
+    // Times out, unless `TEST_KIND_NO_TIMEOUT=1`, when it panics itself instead.
+    if std::env::var("TEST_KIND_NO_TIMEOUT").as_deref() == Ok("1") {
+        panic!("timed out");
+    }
+    std::thread::sleep(Duration::from_secs(5));
+}
+
+#[test_kind(integration, timeout = "10s")]
+#[ignore = "panics, run by panics_are_passed_on_by_timeouts"]
+fn panics_in_time() {
+    panic!("always");
+}
+
+/// The message the test panicked with, if it panicked.
+fn panic_message(test: fn()) -> Option<String> {
+    let panic = std::panic::catch_unwind(test).err()?;
+    panic.downcast_ref::<String>().cloned().or_else(|| {
+        panic
+            .downcast_ref::<&str>()
+            .map(|message| (*message).to_owned())
+    })
+}
+
+#[test]
+fn hanging_tests_time_out() {
+    assert!(panic_message(hangs).is_some_and(|message| message.contains("timed out")));
+}
+
+#[test]
+fn panics_are_passed_on_by_timeouts() {
+    assert_eq!(panic_message(panics_in_time).as_deref(), Some("always"));
+}
+
+#[test_kind(integration, timeout = "100ms", flaky = 2)]
+fn flaky_with_timeout() {
+    // Each attempt has its own timeout.
+}
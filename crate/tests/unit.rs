@@ -9,3 +9,33 @@ fn unit_test_at_earliest_date() {
 fn unit_test_five_days_older() {
     // Test code
 }
+
+#[test_kind(unit, updated = "2023-10-15")]
+fn unit_test_with_quoted_date() {
+    // Test code
+}
+
+#[test_kind(unit, updated = "2023-10-15T09:30:00Z")]
+fn unit_test_with_utc_timestamp() {
+    // Test code
+}
+
+#[test_kind(unit, updated = "2023-10-15T09:30:00+10:00")]
+fn unit_test_with_offset_timestamp() {
+    // Test code
+}
+
+#[test_kind(unit, updated = 2023-10-15, created = 2023-10-10)]
+fn unit_test_with_created_date() {
+    // Test code
+}
+
+#[test_kind(unit, updated = "7d")]
+fn unit_test_updated_days_ago() {
+    // Test code
+}
+
+#[test_kind(unit, updated = "1mo")]
+fn unit_test_updated_months_ago() {
+    // Test code
+}
@@ -0,0 +1,21 @@
+use test_kind::test_kind;
+
+// The wasm harness is only used on `wasm32`, so it does not need to be a dependency here.
+#[test_kind(integration, harness_wasm = "wasm_bindgen_test::wasm_bindgen_test")]
+fn test_with_wasm_harness() {
+    assert_ne!(std::env::consts::ARCH, "wasm32");
+}
+
+#[test_kind(
+    unit,
+    updated = "7d",
+    harness_wasm = "wasm_bindgen_test::wasm_bindgen_test"
+)]
+fn unit_test_with_wasm_harness() -> Result<(), String> {
+    Ok(())
+}
+
+#[test_kind(e2e, resources = not_available, harness_wasm = "wasm_bindgen_test::wasm_bindgen_test")]
+fn skipped_test_with_wasm_harness() {
+    panic!("skipped tests are not run");
+}